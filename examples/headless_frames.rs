@@ -0,0 +1,49 @@
+extern crate sdl2;
+
+use std::env;
+use std::path::Path;
+
+use sdl2_snake::error::GameError;
+use sdl2_snake::events::EventSubscriber;
+use sdl2_snake::game_context::GameContext;
+use sdl2_snake::input::InputAction;
+use sdl2_snake::renderer::Renderer;
+
+// Plays the first seconds of a Classic game offscreen and writes every frame as a BMP,
+// e.g. `cargo run --example headless_frames -- 120 ./frames`
+fn main() -> Result<(), GameError> {
+    let mut args = env::args().skip(1);
+    let frame_count: u32 = match args.next() {
+        Some(count) => count
+            .parse()
+            .map_err(|e| GameError::Config(format!("Invalid frame count: {e}")))?,
+        None => 60,
+    };
+    let output_dir = args.next().unwrap_or_else(|| ".".to_string());
+
+    let ttf_context = sdl2::ttf::init().map_err(|e| GameError::SdlInit(e.to_string()))?;
+    let mut renderer = Renderer::new_headless(&ttf_context)?;
+
+    // Title -> Main Menu -> Mode Select -> Classic
+    let mut context = GameContext::new();
+    for action in [
+        InputAction::Confirm,
+        InputAction::Confirm,
+        InputAction::Confirm,
+    ] {
+        context.handle_input(action);
+    }
+
+    for frame in 0..frame_count {
+        context.update();
+        renderer.handle_events(&context.take_events(), &context);
+        renderer.request_capture();
+        renderer.draw(&context)?;
+        if let Some(mut captured) = renderer.take_capture() {
+            captured.save_bmp(&Path::new(&output_dir).join(format!("frame_{frame:05}.bmp")))?;
+        }
+    }
+
+    println!("Render metrics: {}", renderer.metrics());
+    Ok(())
+}
@@ -0,0 +1,186 @@
+// Finds the game's files so it runs no matter which folder it's started from
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::GameError;
+
+// Points the game at an assets folder somewhere else
+pub const ASSETS_DIR_VAR: &str = "SNAKE_ASSETS_DIR";
+
+// Points the game at another folder for the files it writes, like the high scores
+pub const DATA_DIR_VAR: &str = "SNAKE_DATA_DIR";
+
+// Where an asset was found
+pub enum AssetSource {
+    File(PathBuf),
+    // Built into the game with the `embed-assets` feature
+    Embedded(&'static [u8]),
+}
+
+// The assets folder: $SNAKE_ASSETS_DIR when it's set, otherwise an `assets` folder next to
+// the executable or a few folders above it, so `cargo run` finds the one in the repository,
+// and ./assets as the last resort
+pub fn assets_dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        if let Some(dir) = env::var_os(ASSETS_DIR_VAR) {
+            return PathBuf::from(dir);
+        }
+        env::current_exe()
+            .ok()
+            .and_then(|exe| {
+                exe.ancestors()
+                    .skip(1)
+                    .take(4)
+                    .map(|dir| dir.join("assets"))
+                    .find(|dir| dir.is_dir())
+            })
+            .unwrap_or_else(|| PathBuf::from("./assets"))
+    })
+}
+
+// Where the game keeps what it writes for the player: $SNAKE_DATA_DIR when it's set,
+// otherwise the platform's folder for user data, and the executable's folder on systems
+// without one. Never the working directory, so it doesn't matter where the game is started.
+pub fn data_dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        if let Some(dir) = env::var_os(DATA_DIR_VAR) {
+            return PathBuf::from(dir);
+        }
+        let home = || env::var_os("HOME").map(PathBuf::from);
+        let platform_dir = if cfg!(windows) {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            home().map(|home| home.join("Library/Application Support"))
+        } else {
+            env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home().map(|home| home.join(".local/share")))
+        };
+        platform_dir
+            .map(|dir| dir.join("sdl2-snake"))
+            .or_else(|| {
+                env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            })
+            .unwrap_or_else(|| PathBuf::from("."))
+    })
+}
+
+// Full path of a file the game writes, `relative` is relative to the data folder
+pub fn data_path(relative: &str) -> PathBuf {
+    data_dir().join(relative)
+}
+
+// Full path of an asset, `relative` is relative to the assets folder
+pub fn path(relative: &str) -> PathBuf {
+    assets_dir().join(relative)
+}
+
+pub fn locate(relative: &str) -> Result<AssetSource, GameError> {
+    let path = path(relative);
+    if path.is_file() {
+        return Ok(AssetSource::File(path));
+    }
+    embedded(relative)
+        .map(AssetSource::Embedded)
+        .ok_or_else(|| GameError::Asset {
+            path,
+            message: format!(
+                "the file is missing, set {ASSETS_DIR_VAR} to the folder holding the game's assets"
+            ),
+        })
+}
+
+pub fn read(relative: &str) -> Result<Cow<'static, [u8]>, GameError> {
+    match locate(relative)? {
+        AssetSource::File(path) => match fs::read(&path) {
+            Ok(bytes) => Ok(Cow::Owned(bytes)),
+            Err(e) => Err(GameError::Asset {
+                path,
+                message: e.to_string(),
+            }),
+        },
+        AssetSource::Embedded(bytes) => Ok(Cow::Borrowed(bytes)),
+    }
+}
+
+pub fn read_to_string(relative: &str) -> Result<String, GameError> {
+    String::from_utf8(read(relative)?.into_owned()).map_err(|_| GameError::Asset {
+        path: path(relative),
+        message: "not valid UTF-8 text".to_string(),
+    })
+}
+
+// Every file in an assets folder with the extension, as paths relative to the assets
+// folder in name order. The built-in files are listed when the folder can't be read.
+pub fn list(dir: &str, extension: &str) -> Vec<String> {
+    let full_dir = path(dir);
+    let mut files: Vec<String> = match fs::read_dir(&full_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .filter_map(|path| {
+                path.file_name()
+                    .map(|name| format!("{dir}/{}", name.to_string_lossy()))
+            })
+            .collect(),
+        Err(e) => {
+            let built_in: Vec<String> = EMBEDDED
+                .iter()
+                .map(|(file, _)| file.to_string())
+                .filter(|file| {
+                    Path::new(file).parent() == Some(Path::new(dir))
+                        && Path::new(file)
+                            .extension()
+                            .is_some_and(|ext| ext == extension)
+                })
+                .collect();
+            if built_in.is_empty() {
+                eprintln!("Could not read {}: {e}", full_dir.display());
+            }
+            built_in
+        }
+    };
+    files.sort();
+    files
+}
+
+fn embedded(relative: &str) -> Option<&'static [u8]> {
+    EMBEDDED
+        .iter()
+        .find(|(file, _)| *file == relative)
+        .map(|(_, bytes)| *bytes)
+}
+
+#[cfg(feature = "embed-assets")]
+macro_rules! embed {
+    ($($file:literal),* $(,)?) => {
+        &[$(($file, include_bytes!(concat!("../assets/", $file)) as &[u8])),*]
+    };
+}
+
+#[cfg(feature = "embed-assets")]
+const EMBEDDED: &[(&str, &[u8])] = embed!(
+    "fonts/ArcadeInterlaced-O4d.ttf",
+    "fonts/ArcadeNormal-ZDZ.ttf",
+    "fonts/ArcadeRounded-3DM.ttf",
+    "fonts/DejaVuSans.ttf",
+    "sprites/snake_atlas.bmp",
+    "ui/game.ui",
+    "themes/classic.theme",
+    "themes/gameboy.theme",
+    "themes/neon.theme",
+    "i18n/de.lang",
+    "i18n/en.lang",
+    "i18n/ru.lang",
+);
+
+#[cfg(not(feature = "embed-assets"))]
+const EMBEDDED: &[(&str, &[u8])] = &[];
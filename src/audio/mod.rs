@@ -0,0 +1,7 @@
+pub mod synth;
+
+// Playing through SDL, the synthesizer itself works without it
+#[cfg(feature = "sdl")]
+mod output;
+#[cfg(feature = "sdl")]
+pub use output::Audio;
@@ -0,0 +1,204 @@
+extern crate sdl2;
+
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::Sdl;
+
+use super::synth::{Synth, Tone};
+use crate::data::audio_data;
+use crate::error::GameError;
+use crate::events::{EventSubscriber, GameEvent};
+use crate::game_context::{GameContext, GameState};
+use crate::settings::{Settings, VOLUME_STEPS};
+
+const SAMPLE_RATE: i32 = 44_100;
+// About 12ms of latency at the sample rate
+const BUFFER_SAMPLES: u16 = 512;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Sound {
+    // Carries the snake's new length, the blip rises as it grows
+    Eat(usize),
+    PowerUp,
+    Turn,
+    Death,
+    Pause,
+    Resume,
+    MenuMove,
+    MenuSelect,
+    MenuBack,
+}
+
+impl Sound {
+    fn tones(&self) -> Vec<Tone> {
+        match *self {
+            Sound::Eat(length) => audio_data::eat(length),
+            Sound::PowerUp => audio_data::power_up(),
+            Sound::Turn => audio_data::turn(),
+            Sound::Death => audio_data::death(),
+            Sound::Pause => audio_data::pause(),
+            Sound::Resume => audio_data::resume(),
+            Sound::MenuMove => audio_data::menu_move(),
+            Sound::MenuSelect => audio_data::menu_select(),
+            Sound::MenuBack => audio_data::menu_back(),
+        }
+    }
+
+    // The sound an event makes, if any
+    fn for_event(event: &GameEvent) -> Option<Sound> {
+        match event {
+            GameEvent::Grew { length } => Some(Sound::Eat(*length)),
+            GameEvent::PowerUpCollected { .. } => Some(Sound::PowerUp),
+            GameEvent::Turned { .. } => Some(Sound::Turn),
+            GameEvent::Died { .. } => Some(Sound::Death),
+            GameEvent::Paused => Some(Sound::Pause),
+            GameEvent::Resumed => Some(Sound::Resume),
+            GameEvent::MenuMoved => Some(Sound::MenuMove),
+            GameEvent::MenuSelected => Some(Sound::MenuSelect),
+            GameEvent::MenuBack => Some(Sound::MenuBack),
+            GameEvent::FoodEaten { .. }
+            | GameEvent::Wrapped { .. }
+            | GameEvent::HighScore { .. }
+            | GameEvent::ModeChanged { .. }
+            | GameEvent::ScreenChanged { .. } => None,
+        }
+    }
+}
+
+// Loops played while a screen is up
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Track {
+    Menu,
+    Game,
+    GameOver,
+}
+
+impl Track {
+    fn channels(&self) -> Vec<Vec<Tone>> {
+        match *self {
+            Track::Menu => audio_data::menu_music(),
+            Track::Game => audio_data::game_music(),
+            Track::GameOver => audio_data::game_over_music(),
+        }
+    }
+
+    fn for_state(state: GameState) -> Track {
+        match state {
+            GameState::Title
+            | GameState::MainMenu
+            | GameState::ModeSelect
+            | GameState::Options
+            | GameState::HighScores => Track::Menu,
+            GameState::Playing | GameState::Paused => Track::Game,
+            GameState::Dying | GameState::Over => Track::GameOver,
+        }
+    }
+}
+
+impl AudioCallback for Synth {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.render(out);
+    }
+}
+
+// Plays sound effects and music for the game's events, all synthesized while playing.
+// Without an audio device the game simply stays silent.
+pub struct Audio {
+    output: Option<Output>,
+}
+
+struct Output {
+    device: AudioDevice<Synth>,
+    playing: Option<Track>,
+    // (music, sound) volumes last handed to the synthesizer
+    volumes: Option<(f32, f32)>,
+}
+
+impl Audio {
+    pub fn new(sdl_context: &Sdl) -> Audio {
+        let output = match Output::open(sdl_context) {
+            Ok(output) => Some(output),
+            Err(e) => {
+                eprintln!("Playing without sound: {e}");
+                None
+            }
+        };
+        Audio { output }
+    }
+}
+
+impl EventSubscriber for Audio {
+    fn handle_events(&mut self, events: &[GameEvent], context: &GameContext) {
+        let Some(output) = &mut self.output else {
+            return;
+        };
+        output.apply_volumes(&context.settings);
+
+        for event in events {
+            if let GameEvent::ScreenChanged { state } = event {
+                output.switch_music(*state);
+            }
+            if let Some(sound) = Sound::for_event(event) {
+                output.play(sound);
+            }
+        }
+    }
+}
+
+impl Output {
+    fn open(sdl_context: &Sdl) -> Result<Output, GameError> {
+        let audio_subsys = sdl_context.audio().map_err(GameError::SdlInit)?;
+        let desired = AudioSpecDesired {
+            freq: Some(SAMPLE_RATE),
+            channels: Some(1),
+            samples: Some(BUFFER_SAMPLES),
+        };
+        let device = audio_subsys
+            .open_playback(None, &desired, |spec| Synth::new(spec.freq as u32))
+            .map_err(GameError::SdlInit)?;
+        device.resume();
+
+        Ok(Output {
+            device,
+            playing: None,
+            volumes: None,
+        })
+    }
+
+    fn apply_volumes(&mut self, settings: &Settings) {
+        let scale = |volume: u8| {
+            if settings.muted {
+                0.0
+            } else {
+                volume as f32 / VOLUME_STEPS as f32
+            }
+        };
+        let volumes = (scale(settings.music_volume), scale(settings.sound_volume));
+        if self.volumes != Some(volumes) {
+            let mut synth = self.device.lock();
+            (synth.music_volume, synth.sound_volume) = volumes;
+            self.volumes = Some(volumes);
+        }
+    }
+
+    fn play(&mut self, sound: Sound) {
+        self.device.lock().play(sound.tones());
+    }
+
+    // Pausing keeps the game's music where it was, also through the options opened from the
+    // pause menu. Any other screen starts its own loop.
+    fn switch_music(&mut self, state: GameState) {
+        let mut synth = self.device.lock();
+        if state == GameState::Options && self.playing == Some(Track::Game) {
+            synth.music_paused = true;
+            return;
+        }
+        synth.music_paused = state == GameState::Paused;
+        let track = Track::for_state(state);
+        if self.playing != Some(track) {
+            synth.play_music(track.channels());
+            self.playing = Some(track);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+// Square, triangle and noise oscillators like an old sound chip, rendered into plain sample
+// buffers so the sounds can be checked without an audio device
+
+// Sounds playing at once, the oldest one stops to make room for a new one
+const MAX_SOUNDS: usize = 8;
+// Keeps several full-volume voices from clipping
+const MASTER_GAIN: f32 = 0.3;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Waveform {
+    // Share of each period spent high, 0.5 is the plain square
+    Square(f32),
+    Triangle,
+    Noise,
+}
+
+// One note whose pitch slides from `start_hz` to `end_hz`
+#[derive(Copy, Clone)]
+pub struct Tone {
+    pub waveform: Waveform,
+    pub start_hz: f32,
+    pub end_hz: f32,
+    pub seconds: f32,
+    pub volume: f32,
+    // Fade out over the note instead of stopping at full volume
+    pub fade: bool,
+}
+
+impl Tone {
+    pub fn new(waveform: Waveform, hz: f32, seconds: f32) -> Tone {
+        Tone {
+            waveform,
+            start_hz: hz,
+            end_hz: hz,
+            seconds,
+            volume: 1.0,
+            fade: true,
+        }
+    }
+
+    pub fn rest(seconds: f32) -> Tone {
+        Tone::new(Waveform::Square(0.5), 0.0, seconds).with_volume(0.0)
+    }
+
+    pub fn slide_to(self, end_hz: f32) -> Tone {
+        Tone { end_hz, ..self }
+    }
+
+    pub fn with_volume(self, volume: f32) -> Tone {
+        Tone { volume, ..self }
+    }
+
+    pub fn held(self) -> Tone {
+        Tone {
+            fade: false,
+            ..self
+        }
+    }
+}
+
+// Frequency of a MIDI note number, 69 is the A at 440 Hz
+pub fn note_hz(note: u8) -> f32 {
+    440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+// Plays tones one after the other, music voices start over at the end
+pub struct Voice {
+    tones: Vec<Tone>,
+    looping: bool,
+    index: usize,
+    // Samples into the current tone
+    position: u32,
+    // How far into the current period, from 0 up to 1
+    phase: f32,
+    // Shift register of the noise channel, it steps once every period
+    noise: u16,
+}
+
+impl Voice {
+    pub fn new(tones: Vec<Tone>, looping: bool) -> Voice {
+        Voice {
+            tones,
+            looping,
+            index: 0,
+            position: 0,
+            phase: 0.0,
+            noise: 1,
+        }
+    }
+
+    // The next sample, None once the last tone has ended
+    pub fn next_sample(&mut self, sample_rate: f32) -> Option<f32> {
+        if self.index >= self.tones.len() {
+            if !self.looping || self.tones.is_empty() {
+                return None;
+            }
+            self.index = 0;
+        }
+        let tone = self.tones[self.index];
+        let length = ((tone.seconds * sample_rate) as u32).max(1);
+        let progress = self.position as f32 / length as f32;
+
+        let value = match tone.waveform {
+            Waveform::Square(duty) => {
+                if self.phase < duty {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Triangle => 4.0 * (self.phase - 0.5).abs() - 1.0,
+            Waveform::Noise => {
+                if self.noise & 1 == 1 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        };
+        let envelope = if tone.fade { 1.0 - progress } else { 1.0 };
+
+        let hz = tone.start_hz + (tone.end_hz - tone.start_hz) * progress;
+        self.phase += hz / sample_rate;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            let feedback = (self.noise ^ (self.noise >> 1)) & 1;
+            self.noise = (self.noise >> 1) | (feedback << 14);
+        }
+
+        self.position += 1;
+        if self.position >= length {
+            self.position = 0;
+            self.index += 1;
+        }
+        Some(value * tone.volume * envelope)
+    }
+}
+
+// Mixes the sound effects and the music. Runs inside the audio callback, the game changes
+// it through the device lock.
+pub struct Synth {
+    sample_rate: f32,
+    sounds: Vec<Voice>,
+    music: Vec<Voice>,
+    pub music_paused: bool,
+    // 0.0 to 1.0
+    pub music_volume: f32,
+    pub sound_volume: f32,
+}
+
+impl Synth {
+    pub fn new(sample_rate: u32) -> Synth {
+        Synth {
+            sample_rate: sample_rate as f32,
+            sounds: Vec::new(),
+            music: Vec::new(),
+            music_paused: false,
+            music_volume: 1.0,
+            sound_volume: 1.0,
+        }
+    }
+
+    pub fn play(&mut self, tones: Vec<Tone>) {
+        if self.sounds.len() >= MAX_SOUNDS {
+            self.sounds.remove(0);
+        }
+        self.sounds.push(Voice::new(tones, false));
+    }
+
+    // Replaces the music, every channel loops on its own
+    pub fn play_music(&mut self, channels: Vec<Vec<Tone>>) {
+        self.music = channels
+            .into_iter()
+            .map(|tones| Voice::new(tones, true))
+            .collect();
+        self.music_paused = false;
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.sounds.is_empty() && (self.music.is_empty() || self.music_paused)
+    }
+
+    // Mono samples from -1.0 to 1.0
+    pub fn render(&mut self, out: &mut [f32]) {
+        let sample_rate = self.sample_rate;
+        for sample in out.iter_mut() {
+            let mut mixed = 0.0;
+            self.sounds
+                .retain_mut(|voice| match voice.next_sample(sample_rate) {
+                    Some(value) => {
+                        mixed += value * self.sound_volume;
+                        true
+                    }
+                    None => false,
+                });
+            if !self.music_paused {
+                for voice in &mut self.music {
+                    mixed += voice.next_sample(sample_rate).unwrap_or(0.0) * self.music_volume;
+                }
+            }
+            *sample = (mixed * MASTER_GAIN).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::audio_data;
+
+    const RATE: u32 = 44_100;
+
+    fn render(synth: &mut Synth, seconds: f32) -> Vec<f32> {
+        let mut buffer = vec![0.0; (seconds * RATE as f32) as usize];
+        synth.render(&mut buffer);
+        buffer
+    }
+
+    fn render_tones(tones: Vec<Tone>) -> Vec<f32> {
+        let seconds = tones.iter().map(|tone| tone.seconds).sum();
+        let mut synth = Synth::new(RATE);
+        synth.play(tones);
+        render(&mut synth, seconds)
+    }
+
+    // Times the wave goes from negative to positive, which is its frequency for one second
+    fn rising_crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+            .count()
+    }
+
+    #[test]
+    fn square_wave_has_its_frequency_and_duty_cycle() {
+        let samples = render_tones(vec![Tone::new(Waveform::Square(0.25), 441.0, 1.0).held()]);
+        assert!((rising_crossings(&samples) as i32 - 441).abs() <= 1);
+
+        let high = samples.iter().filter(|sample| **sample > 0.0).count();
+        let share = high as f32 / samples.len() as f32;
+        assert!((share - 0.25).abs() < 0.01, "high for {share} of the time");
+        assert!(samples
+            .iter()
+            .all(|sample| (sample.abs() - MASTER_GAIN).abs() < 1e-6));
+    }
+
+    #[test]
+    fn triangle_wave_ramps_between_its_peaks() {
+        let samples = render_tones(vec![Tone::new(Waveform::Triangle, 100.0, 0.5).held()]);
+        let max = samples.iter().cloned().fold(f32::MIN, f32::max);
+        let min = samples.iter().cloned().fold(f32::MAX, f32::min);
+        assert!((max - MASTER_GAIN).abs() < 0.01 && (min + MASTER_GAIN).abs() < 0.01);
+
+        // No jumps like a square wave has, one step is at most 4 * gain * hz / rate
+        let steepest = samples
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0, f32::max);
+        assert!(steepest <= 4.0 * MASTER_GAIN * 100.0 / RATE as f32 + 1e-4);
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        assert!(mean.abs() < 0.01);
+    }
+
+    #[test]
+    fn noise_is_irregular_but_repeatable() {
+        let tones = || vec![Tone::new(Waveform::Noise, 4000.0, 0.25).held()];
+        let samples = render_tones(tones());
+        assert_eq!(samples, render_tones(tones()));
+
+        let positive = samples.iter().filter(|sample| **sample > 0.0).count();
+        let share = positive as f32 / samples.len() as f32;
+        assert!(
+            share > 0.3 && share < 0.7,
+            "positive for {share} of the time"
+        );
+        assert!(rising_crossings(&samples) > 100);
+    }
+
+    #[test]
+    fn sound_effects_fade_out_and_end() {
+        let mut synth = Synth::new(RATE);
+        synth.play(vec![Tone::new(Waveform::Square(0.5), 500.0, 0.1)]);
+        let samples = render(&mut synth, 0.2);
+        let (sound, after) = samples.split_at(RATE as usize / 10);
+
+        let loudness = |part: &[f32]| part.iter().map(|sample| sample.abs()).sum::<f32>();
+        let quarter = sound.len() / 4;
+        assert!(loudness(&sound[..quarter]) > 2.0 * loudness(&sound[3 * quarter..]));
+        assert!(after.iter().all(|sample| *sample == 0.0));
+        assert!(synth.is_silent());
+    }
+
+    #[test]
+    fn music_loops_and_pauses() {
+        let mut synth = Synth::new(RATE);
+        synth.play_music(vec![vec![
+            Tone::new(Waveform::Triangle, 220.0, 0.1).held(),
+            Tone::rest(0.1),
+        ]]);
+        let samples = render(&mut synth, 1.0);
+        let tenth = RATE as usize / 10;
+        for (i, part) in samples.chunks(tenth).enumerate() {
+            let sounding = part.iter().any(|sample| *sample != 0.0);
+            assert_eq!(sounding, i % 2 == 0, "tenth {i}");
+        }
+
+        synth.music_paused = true;
+        assert!(render(&mut synth, 0.1).iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn volume_scales_sounds_and_music_separately() {
+        let mut synth = Synth::new(RATE);
+        synth.sound_volume = 0.0;
+        synth.music_volume = 0.5;
+        synth.play(vec![Tone::new(Waveform::Square(0.5), 300.0, 0.1).held()]);
+        synth.play_music(vec![vec![
+            Tone::new(Waveform::Square(0.5), 300.0, 0.1).held()
+        ]]);
+        let samples = render(&mut synth, 0.1);
+        assert!(samples
+            .iter()
+            .all(|sample| (sample.abs() - 0.5 * MASTER_GAIN).abs() < 1e-6));
+    }
+
+    #[test]
+    fn too_many_sounds_drop_the_oldest() {
+        let mut synth = Synth::new(RATE);
+        for _ in 0..MAX_SOUNDS + 3 {
+            synth.play(vec![Tone::new(Waveform::Square(0.5), 300.0, 1.0).held()]);
+        }
+        let samples = render(&mut synth, 0.01);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        assert_eq!(synth.sounds.len(), MAX_SOUNDS);
+    }
+
+    #[test]
+    fn eating_sounds_higher_as_the_snake_grows() {
+        let pitch = |length| {
+            let tones = audio_data::eat(length);
+            let seconds: f32 = tones.iter().map(|tone| tone.seconds).sum();
+            rising_crossings(&render_tones(tones)) as f32 / seconds
+        };
+        assert!(pitch(3) < pitch(10));
+        assert!(pitch(10) < pitch(20));
+        // It stops rising so long snakes don't squeak
+        assert_eq!(pitch(500), pitch(1000));
+    }
+
+    #[test]
+    fn death_jingle_descends() {
+        let tones: Vec<Tone> = audio_data::death()
+            .into_iter()
+            .filter(|tone| tone.waveform != Waveform::Noise && tone.volume > 0.0)
+            .collect();
+        assert!(tones.len() >= 3);
+        let pitches: Vec<f32> = tones
+            .iter()
+            .map(|tone| rising_crossings(&render_tones(vec![*tone])) as f32 / tone.seconds)
+            .collect();
+        assert!(
+            pitches.windows(2).all(|pair| pair[1] < pair[0]),
+            "{pitches:?}"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{self, Print, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+
+use sdl2_snake::color::Color;
+use sdl2_snake::constants::{FRAMES_PER_SECOND, GRID_X_SIZE, GRID_Y_SIZE};
+use sdl2_snake::entities::rich_text;
+use sdl2_snake::entities::text_elements::TextElement;
+use sdl2_snake::events::GameEvent;
+use sdl2_snake::game_context::{GameContext, GameState, Point};
+use sdl2_snake::input::InputAction;
+
+// Plays the game in a terminal, two board rows per text row using half block characters
+fn main() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    // Leave the terminal usable even if the game panics
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal(&mut io::stdout());
+        default_hook(info);
+    }));
+
+    let result = run(&mut stdout);
+    restore_terminal(&mut stdout)?;
+    result
+}
+
+fn run(stdout: &mut Stdout) -> io::Result<()> {
+    let mut context = GameContext::new();
+    let frame_time = Duration::new(0, 1_000_000_000u32 / FRAMES_PER_SECOND);
+
+    loop {
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if is_quit(&key) {
+                    return Ok(());
+                }
+                if key.kind != KeyEventKind::Release {
+                    if let Some(action) = input_action(key.code) {
+                        context.handle_input(action);
+                    }
+                }
+            }
+        }
+
+        if context.quit_requested {
+            return Ok(());
+        }
+
+        std::thread::sleep(frame_time);
+
+        context.update();
+        // Effects aren't drawn in the terminal, but the bell can stand in for the death sound
+        let events = context.take_events();
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Died { .. }))
+        {
+            queue!(stdout, Print('\x07'))?;
+        }
+        draw(stdout, &context)?;
+    }
+}
+
+fn restore_terminal(stdout: &mut Stdout) -> io::Result<()> {
+    execute!(
+        stdout,
+        style::ResetColor,
+        cursor::Show,
+        terminal::LeaveAlternateScreen
+    )?;
+    terminal::disable_raw_mode()
+}
+
+fn is_quit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+fn input_action(code: KeyCode) -> Option<InputAction> {
+    match code {
+        KeyCode::Left | KeyCode::Char('a') => Some(InputAction::Left),
+        KeyCode::Up | KeyCode::Char('w') => Some(InputAction::Up),
+        KeyCode::Right | KeyCode::Char('d') => Some(InputAction::Right),
+        KeyCode::Down | KeyCode::Char('s') => Some(InputAction::Down),
+        KeyCode::Enter | KeyCode::Char(' ') => Some(InputAction::Confirm),
+        KeyCode::Esc | KeyCode::Backspace => Some(InputAction::Back),
+        _ => None,
+    }
+}
+
+fn draw(stdout: &mut Stdout, context: &GameContext) -> io::Result<()> {
+    queue!(stdout, cursor::MoveTo(0, 0))?;
+
+    for row in 0..(GRID_Y_SIZE as i32 + 1) / 2 {
+        for x in 0..GRID_X_SIZE as i32 {
+            let top = cell_color(context, Point(x, row * 2));
+            let bottom = cell_color(context, Point(x, row * 2 + 1));
+            queue!(
+                stdout,
+                SetForegroundColor(to_terminal_color(top)),
+                SetBackgroundColor(to_terminal_color(bottom)),
+                Print('▀')
+            )?;
+        }
+        queue!(
+            stdout,
+            style::ResetColor,
+            terminal::Clear(ClearType::UntilNewLine),
+            Print("\r\n")
+        )?;
+    }
+
+    // HUD and overlays go below the board as plain lines
+    let mut elements = context
+        .text_elements
+        .values()
+        // Score popups only make sense drawn on the board
+        .filter(|element| element.visible && !element.animation.remove_when_finished)
+        .collect::<Vec<&TextElement>>();
+    elements.sort_by_key(|element| element.draw_order);
+    let variables = context.variables();
+    for element in elements {
+        let mut lines = element.lines.values().collect::<Vec<_>>();
+        lines.sort_by_key(|line| line.vertical_order);
+        for line in lines {
+            queue!(
+                stdout,
+                SetForegroundColor(to_terminal_color(
+                    context.theme().resolve_font(&line.font).color
+                )),
+                Print(rich_text::plain_text(&line.resolved_text(&variables))),
+                style::ResetColor,
+                terminal::Clear(ClearType::UntilNewLine),
+                Print("\r\n")
+            )?;
+        }
+    }
+    queue!(stdout, terminal::Clear(ClearType::FromCursorDown))?;
+
+    stdout.flush()
+}
+
+// Same theme as the SDL renderer
+fn cell_color(context: &GameContext, point: Point) -> Color {
+    let theme = context.theme();
+    // Menus and other screens without the board are just their background, like in the window
+    if !context.state.shows_board() {
+        return theme.background(context.state);
+    }
+    if let Some(color) = entity_color(context, point) {
+        return color;
+    }
+
+    theme
+        .terrain(context.terrain.get(point))
+        .unwrap_or(match (context.state, &context.death) {
+            (GameState::Dying, Some(death)) if death.is_flash_on() => theme.death_flash,
+            (state, _) => theme.background(state),
+        })
+}
+
+fn entity_color(context: &GameContext, point: Point) -> Option<Color> {
+    let theme = context.theme();
+    if let (GameState::Dying, Some(death)) = (context.state, &context.death) {
+        let hit = death
+            .segments
+            .iter()
+            .any(|s| Point(s.x.round() as i32, s.y.round() as i32) == point);
+        if hit && death.segment_alpha() > 127 {
+            return Some(if death.is_flash_on() && !death.is_scattering() {
+                theme.snake_death_flash
+            } else {
+                theme.snake_head
+            });
+        }
+    } else if context.state != GameState::Over {
+        let length = context.player_position.len();
+        if let Some(i) = context.player_position.iter().position(|p| *p == point) {
+            return Some(if context.has_wall_breaker {
+                theme.snake_wall_breaker
+            } else {
+                theme.snake_segment(i, length)
+            });
+        }
+    }
+
+    if context.food == point {
+        return Some(theme.food);
+    }
+    if context.power_up == Some(point) {
+        return Some(theme.power_up);
+    }
+    context
+        .actors
+        .iter()
+        .find(|actor| actor.alive && actor.position == point)
+        .map(|actor| theme.actor(actor.kind))
+}
+
+fn to_terminal_color(color: Color) -> style::Color {
+    style::Color::Rgb {
+        r: color.r,
+        g: color.g,
+        b: color.b,
+    }
+}
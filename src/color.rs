@@ -0,0 +1,42 @@
+// Colors for the game itself, so it doesn't need SDL. Mirrors sdl2::pixels::Color, which
+// the SDL frontend converts to with `.into()`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[allow(non_snake_case)]
+impl Color {
+    pub const WHITE: Color = Color::RGB(255, 255, 255);
+    pub const BLACK: Color = Color::RGB(0, 0, 0);
+    pub const RED: Color = Color::RGB(255, 0, 0);
+    pub const GREEN: Color = Color::RGB(0, 255, 0);
+    pub const YELLOW: Color = Color::RGB(255, 255, 0);
+    pub const CYAN: Color = Color::RGB(0, 255, 255);
+    pub const MAGENTA: Color = Color::RGB(255, 0, 255);
+
+    pub const fn RGB(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn RGBA(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+}
+
+#[cfg(feature = "sdl")]
+impl From<Color> for sdl2::pixels::Color {
+    fn from(color: Color) -> sdl2::pixels::Color {
+        sdl2::pixels::Color::RGBA(color.r, color.g, color.b, color.a)
+    }
+}
+
+#[cfg(feature = "sdl")]
+impl From<sdl2::pixels::Color> for Color {
+    fn from(color: sdl2::pixels::Color) -> Color {
+        Color::RGBA(color.r, color.g, color.b, color.a)
+    }
+}
@@ -1,5 +1,21 @@
 pub const GRID_X_SIZE: u32 = 40;
 pub const GRID_Y_SIZE: u32 = 30;
 pub const DOT_SIZE_IN_PXS: u32 = 20;
+// Logical size of the game screen, scaled to whatever size the window has
+pub const SCREEN_WIDTH: u32 = GRID_X_SIZE * DOT_SIZE_IN_PXS;
+pub const SCREEN_HEIGHT: u32 = GRID_Y_SIZE * DOT_SIZE_IN_PXS;
 pub const FRAMES_PER_SECOND: u32 = 60;
-pub const FONT_PATH: &str = "./assets/fonts/";
+// Relative to the assets folder, see assets.rs
+pub const FONT_PATH: &str = "fonts";
+pub const SPRITE_ATLAS_PATH: &str = "sprites/snake_atlas.bmp";
+pub const UI_LAYOUT_PATH: &str = "ui/game.ui";
+pub const THEMES_PATH: &str = "themes";
+pub const I18N_PATH: &str = "i18n";
+// Tried after the language's own fonts for glyphs the arcade fonts lack
+pub const FALLBACK_FONTS: [&str; 1] = ["DejaVuSans.ttf"];
+pub const TRANSITION_FRAMES: u32 = 15;
+pub const MAX_HIGH_SCORES: usize = 10;
+// File name of the high scores inside the data folder, see assets::data_dir
+pub const HIGH_SCORES_PATH: &str = "highscores.txt";
+pub const COUNTDOWN_FRAMES: u32 = 3 * FRAMES_PER_SECOND;
+pub const MAX_BUFFERED_TURNS: usize = 3;
@@ -0,0 +1,12 @@
+// Define the moving entities placed on a fresh board here
+use crate::{entities::actors::Actor, game_context::Point};
+
+pub const MOUSE_BONUS: i32 = 5;
+
+pub fn get_initial_actors(no_intersect: &Vec<Point>) -> Vec<Actor> {
+    vec![
+        Actor::new_bouncing_ball(Point(5, 5), Point(1, 1)),
+        Actor::new_patrol_block(Point(10, 24), Point(1, 0), 18),
+        Actor::new_mouse(Point::new_no_intersect(no_intersect), MOUSE_BONUS),
+    ]
+}
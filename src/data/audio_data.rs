@@ -0,0 +1,151 @@
+// Define the game's sound effects and music here, as notes for the synthesizer
+use crate::audio::synth::{note_hz, Tone, Waveform};
+
+// Marks a step without a note in the music below
+const REST: u8 = 0;
+
+const PULSE: Waveform = Waveform::Square(0.5);
+const THIN_PULSE: Waveform = Waveform::Square(0.25);
+
+// A blip that climbs a semitone for every two segments the snake has grown, up to two octaves
+pub fn eat(length: usize) -> Vec<Tone> {
+    let steps = (length.saturating_sub(3) / 2).min(24) as u8;
+    let hz = note_hz(72 + steps);
+    vec![Tone::new(PULSE, hz, 0.07).slide_to(hz * 1.5)]
+}
+
+pub fn power_up() -> Vec<Tone> {
+    [72, 76, 79, 84]
+        .iter()
+        .map(|note| Tone::new(THIN_PULSE, note_hz(*note), 0.05))
+        .collect()
+}
+
+pub fn turn() -> Vec<Tone> {
+    vec![Tone::new(Waveform::Triangle, 330.0, 0.03)
+        .slide_to(260.0)
+        .with_volume(0.6)]
+}
+
+// Four falling notes and a crash
+pub fn death() -> Vec<Tone> {
+    let mut tones: Vec<Tone> = [67, 63, 60, 55]
+        .iter()
+        .map(|note| Tone::new(PULSE, note_hz(*note), 0.14))
+        .collect();
+    tones.push(Tone::new(Waveform::Noise, 3000.0, 0.4).slide_to(400.0));
+    tones
+}
+
+pub fn pause() -> Vec<Tone> {
+    vec![
+        Tone::new(THIN_PULSE, note_hz(76), 0.06),
+        Tone::new(THIN_PULSE, note_hz(71), 0.08),
+    ]
+}
+
+pub fn resume() -> Vec<Tone> {
+    vec![
+        Tone::new(THIN_PULSE, note_hz(71), 0.06),
+        Tone::new(THIN_PULSE, note_hz(76), 0.08),
+    ]
+}
+
+pub fn menu_move() -> Vec<Tone> {
+    vec![Tone::new(THIN_PULSE, note_hz(84), 0.025).with_volume(0.5)]
+}
+
+pub fn menu_select() -> Vec<Tone> {
+    vec![
+        Tone::new(PULSE, note_hz(72), 0.04),
+        Tone::new(PULSE, note_hz(79), 0.07),
+    ]
+}
+
+pub fn menu_back() -> Vec<Tone> {
+    vec![
+        Tone::new(PULSE, note_hz(79), 0.04),
+        Tone::new(PULSE, note_hz(72), 0.07),
+    ]
+}
+
+pub fn menu_music() -> Vec<Vec<Tone>> {
+    vec![
+        pattern(
+            THIN_PULSE,
+            &[
+                72, REST, 76, 79, 77, REST, 76, 74, 72, REST, 74, 76, 74, REST, 71, REST,
+            ],
+            0.25,
+            0.35,
+        ),
+        pattern(
+            Waveform::Triangle,
+            &[
+                48, 48, 55, 55, 53, 53, 55, 55, 48, 48, 53, 53, 55, 55, 43, 43,
+            ],
+            0.25,
+            0.5,
+        ),
+    ]
+}
+
+pub fn game_music() -> Vec<Vec<Tone>> {
+    vec![
+        pattern(
+            PULSE,
+            &[
+                64, 67, 71, 67, 64, 67, 72, 71, 62, 65, 69, 65, 62, 65, 71, 69,
+            ],
+            0.15,
+            0.25,
+        ),
+        pattern(
+            Waveform::Triangle,
+            &[
+                40, REST, 47, REST, 40, REST, 47, REST, 38, REST, 45, REST, 38, REST, 43, REST,
+            ],
+            0.15,
+            0.5,
+        ),
+        pattern(
+            Waveform::Noise,
+            &[REST, REST, 96, REST, REST, REST, 96, REST],
+            0.15,
+            0.15,
+        ),
+    ]
+}
+
+pub fn game_over_music() -> Vec<Vec<Tone>> {
+    vec![
+        pattern(
+            THIN_PULSE,
+            &[
+                67, REST, 64, REST, 60, REST, REST, REST, 62, REST, 59, REST, 55, REST, REST, REST,
+            ],
+            0.3,
+            0.3,
+        ),
+        pattern(
+            Waveform::Triangle,
+            &[
+                36, REST, REST, REST, 41, REST, REST, REST, 43, REST, REST, REST, 36, REST, REST,
+                REST,
+            ],
+            0.3,
+            0.5,
+        ),
+    ]
+}
+
+// One channel of music, a note or rest every `step` seconds
+fn pattern(waveform: Waveform, notes: &[u8], step: f32, volume: f32) -> Vec<Tone> {
+    notes
+        .iter()
+        .map(|note| match *note {
+            REST => Tone::rest(step),
+            note => Tone::new(waveform, note_hz(note), step).with_volume(volume),
+        })
+        .collect()
+}
@@ -0,0 +1,146 @@
+// Minimal `key = value` file format with `[section]` headers and `#` comments,
+// shared by the asset files under assets/
+use crate::assets;
+use crate::error::GameError;
+
+pub struct KvEntry {
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+pub struct KvFile {
+    pub path: String,
+    pub entries: Vec<KvEntry>,
+    // Section names with the line of their header, in file order
+    pub sections: Vec<(String, usize)>,
+}
+
+impl KvFile {
+    // `path` is relative to the assets folder, errors name the full path
+    pub fn load(path: &str) -> Result<KvFile, GameError> {
+        let source = assets::read_to_string(path)?;
+        KvFile::parse(&source, &assets::path(path).display().to_string())
+    }
+
+    pub fn parse(source: &str, path: &str) -> Result<KvFile, GameError> {
+        let mut entries = Vec::new();
+        let mut sections = Vec::new();
+        let mut section = String::new();
+
+        for (i, raw_line) in source.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                section = name
+                    .strip_suffix(']')
+                    .ok_or(GameError::Config(format!(
+                        "{path}:{}: section header is missing `]`",
+                        i + 1
+                    )))?
+                    .trim()
+                    .to_string();
+                sections.push((section.clone(), i + 1));
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(GameError::Config(format!(
+                "{path}:{}: expected `key = value`",
+                i + 1
+            )))?;
+            entries.push(KvEntry {
+                section: section.clone(),
+                key: key.trim().to_string(),
+                value: unquote(value.trim()).to_string(),
+                line: i + 1,
+            });
+        }
+
+        Ok(KvFile {
+            path: path.to_string(),
+            entries,
+            sections,
+        })
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&KvEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.section == section && entry.key == key)
+    }
+
+    pub fn section<'a>(&'a self, section: &'a str) -> impl Iterator<Item = &'a KvEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.section == section)
+    }
+
+    // Error message pointing at the line the entry came from
+    pub fn error(&self, entry: &KvEntry, message: &str) -> GameError {
+        GameError::Config(format!("{}:{}: {message}", self.path, entry.line))
+    }
+}
+
+// Values may be quoted to keep leading or trailing spaces
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(source: &str) -> String {
+        KvFile::parse(source, "test.kv")
+            .err()
+            .unwrap()
+            .config_message()
+    }
+
+    #[test]
+    fn reads_sections_keys_and_values() {
+        let source =
+            "# comment\nname = Classic\n\n[colors]\n  food = #FF0000  \nlabel = \" padded \"\n";
+        let file = KvFile::parse(source, "test.kv").unwrap();
+
+        assert_eq!(file.sections, vec![(String::from("colors"), 4)]);
+        assert_eq!(file.get("", "name").unwrap().value, "Classic");
+        let food = file.get("colors", "food").unwrap();
+        assert_eq!((food.value.as_str(), food.line), ("#FF0000", 5));
+        assert_eq!(file.get("colors", "label").unwrap().value, " padded ");
+        assert!(file.get("", "food").is_none());
+        assert_eq!(file.section("colors").count(), 2);
+    }
+
+    #[test]
+    fn values_may_contain_equals_signs() {
+        let file = KvFile::parse("text = a = b", "test.kv").unwrap();
+        assert_eq!(file.get("", "text").unwrap().value, "a = b");
+    }
+
+    #[test]
+    fn errors_name_the_file_and_line() {
+        assert_eq!(
+            parse_error("[colors]\n[fonts\n"),
+            "test.kv:2: section header is missing `]`"
+        );
+        assert_eq!(
+            parse_error("[colors]\nfood #FF0000\n"),
+            "test.kv:2: expected `key = value`"
+        );
+
+        let file = KvFile::parse("\n[a]\nkey = value", "test.kv").unwrap();
+        let entry = file.get("a", "key").unwrap();
+        assert_eq!(
+            file.error(entry, "bad value").config_message(),
+            "test.kv:3: bad value"
+        );
+    }
+}
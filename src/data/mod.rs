@@ -1 +1,6 @@
+pub mod actor_data;
+pub mod audio_data;
+pub mod kv_file;
+pub mod terrain_data;
 pub mod text_data;
+pub mod ui_layout;
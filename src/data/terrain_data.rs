@@ -0,0 +1,34 @@
+// Define the terrain layout of a fresh board here
+use crate::{
+    entities::terrain::{TerrainMap, TerrainTile},
+    game_context::{PlayerDirection, Point},
+};
+
+pub fn get_default_terrain() -> TerrainMap {
+    let mut terrain = TerrainMap::new();
+
+    // Frozen pond in the top left
+    terrain.fill(Point(4, 3), Point(9, 6), TerrainTile::Ice);
+
+    // Conveyor belts pushing across the top right
+    terrain.fill(
+        Point(26, 4),
+        Point(34, 4),
+        TerrainTile::Conveyor(PlayerDirection::Down),
+    );
+    terrain.fill(
+        Point(26, 8),
+        Point(34, 8),
+        TerrainTile::Conveyor(PlayerDirection::Up),
+    );
+
+    // Mud pit in the bottom right
+    terrain.fill(Point(28, 20), Point(33, 24), TerrainTile::Mud);
+
+    // Breakable wall with one-way gates at both ends
+    terrain.fill(Point(4, 20), Point(4, 26), TerrainTile::BreakableWall);
+    terrain.set(Point(4, 19), TerrainTile::OneWayGate(PlayerDirection::Down));
+    terrain.set(Point(4, 27), TerrainTile::OneWayGate(PlayerDirection::Up));
+
+    terrain
+}
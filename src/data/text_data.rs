@@ -1,97 +1,108 @@
 // Define text elements and lines here
-extern crate sdl2;
-use sdl2::pixels::Color;
-
 use std::collections::HashMap;
 
 use crate::{
-    data_structs::Position2D,
-    entities::text_elements::{FontDefinition, FontName, TextAlignment, TextElement, TextLine},
+    constants::{MAX_HIGH_SCORES, UI_LAYOUT_PATH},
+    data::{kv_file::KvFile, ui_layout},
+    entities::{
+        menu::{Menu, MenuAction, MenuItem, VolumeChannel},
+        text_elements::TextElement,
+    },
+    game_context::GameMode,
 };
 
+// The layout comes from assets/ui, the copy built into the game is used when that file
+// can't be read or has mistakes
 pub fn get_game_context_text() -> HashMap<String, TextElement> {
-    let mut text_elements: HashMap<String, TextElement> = HashMap::new();
+    ui_layout::load(UI_LAYOUT_PATH).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        let file = KvFile::parse(DEFAULT_UI_LAYOUT, "built-in layout")
+            .expect("the built-in layout is valid");
+        ui_layout::parse(&file).expect("the built-in layout is valid")
+    })
+}
 
-    let mut score_text_element =
-        TextElement::new(0, Position2D::new(5, 5), TextAlignment::Start, false);
-    let score_text_line = TextLine::new(
-        0,
-        Position2D::new(5, 5),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeNormal, 24, Color::WHITE),
-        String::from("0"),
-    );
-    score_text_element
-        .lines
-        .insert("score_line".to_string(), score_text_line);
+const DEFAULT_UI_LAYOUT: &str = include_str!("../../assets/ui/game.ui");
 
-    let mut mode_text_element =
-        TextElement::new(0, Position2D::new(5, 5), TextAlignment::End, false);
-    let mode_text_line = TextLine::new(
-        0,
-        Position2D::new(5, 5),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeNormal, 24, Color::WHITE),
-        String::from("Normal"),
-    );
-    mode_text_element
-        .lines
-        .insert("game_mode_line".to_string(), mode_text_line);
+// Score, length, time, food, cause of death and the high score notice
+const SUMMARY_LINES: u32 = 6;
+
+pub fn get_menus() -> HashMap<String, Menu> {
+    let mut menus: HashMap<String, Menu> = HashMap::new();
 
-    // PAUSE MENU TEXT
-    let mut pause_text_element =
-        TextElement::new(1, Position2D::new(5, 5), TextAlignment::Start, true);
-    let pause_title_line = TextLine::new(
-        0,
-        Position2D::new(0, 0),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeInterlaced, 48, Color::WHITE),
-        String::from("Paused"),
+    let main_menu = Menu::new(
+        vec![
+            MenuItem::new("menu_play", MenuAction::OpenModeSelect),
+            MenuItem::new("menu_options", MenuAction::OpenOptions),
+            MenuItem::new("menu_high_scores", MenuAction::OpenHighScores),
+            MenuItem::new("menu_quit", MenuAction::Quit),
+        ],
+        1,
     );
-    let pause_esc_line = TextLine::new(
+
+    let mode_select_menu = Menu::new(
+        vec![
+            MenuItem::new("mode_classic", MenuAction::StartGame(GameMode::Classic)),
+            MenuItem::new("mode_wall_pass", MenuAction::StartGame(GameMode::WallPass)),
+            MenuItem::new("menu_back", MenuAction::Back),
+        ],
         1,
-        Position2D::new(0, 0),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeRounded, 24, Color::WHITE),
-        String::from("Press ESC to continue"),
     );
-    let pause_mode_line = TextLine::new(
-        2,
-        Position2D::new(0, 0),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeRounded, 24, Color::WHITE),
-        String::from("Press M to change mode"),
+
+    // Setting labels are filled in from the current settings
+    let options_menu = Menu::new(
+        vec![
+            MenuItem::new("option_speed", MenuAction::CycleSpeed),
+            MenuItem::new("option_theme", MenuAction::CycleTheme),
+            MenuItem::new("option_colors", MenuAction::CycleColorPreset),
+            MenuItem::new("option_shapes", MenuAction::ToggleShapeMarkers),
+            MenuItem::new("option_reduced_motion", MenuAction::ToggleReducedMotion),
+            MenuItem::new("option_movement", MenuAction::ToggleSmoothMovement),
+            MenuItem::new("option_effects", MenuAction::CycleEffects),
+            MenuItem::new("option_language", MenuAction::CycleLanguage),
+            MenuItem::new(
+                "option_music",
+                MenuAction::ChangeVolume(VolumeChannel::Music, 1),
+            ),
+            MenuItem::new(
+                "option_sound",
+                MenuAction::ChangeVolume(VolumeChannel::Sound, 1),
+            ),
+            MenuItem::new("option_mute", MenuAction::ToggleMute),
+            MenuItem::new("menu_back", MenuAction::Back),
+        ],
+        1,
     );
 
-    pause_text_element
-        .lines
-        .insert("pause_title_line".to_string(), pause_title_line);
-    pause_text_element
-        .lines
-        .insert("pause_esc_line".to_string(), pause_esc_line);
-    pause_text_element
-        .lines
-        .insert("pause_mode_line".to_string(), pause_mode_line);
+    // Score lines take the orders before the Back item
+    let high_scores_menu = Menu::new(
+        vec![MenuItem::new("menu_back", MenuAction::Back)],
+        MAX_HIGH_SCORES as u32 + 1,
+    );
 
-    // GAME OVER MENU TEXT
-    let mut game_over_text_element =
-        TextElement::new(1, Position2D::new(5, 5), TextAlignment::Start, true);
-    game_over_text_element.visible = false;
-    let game_over_title_line = TextLine::new(
-        0,
-        Position2D::new(0, 0),
-        TextAlignment::Start,
-        FontDefinition::new(FontName::ArcadeInterlaced, 48, Color::WHITE),
-        "Game Over".to_string(),
+    let pause_menu = Menu::new(
+        vec![
+            MenuItem::new("menu_resume", MenuAction::Resume),
+            MenuItem::new("menu_change_mode", MenuAction::OpenModeSelect),
+            MenuItem::new("menu_options", MenuAction::OpenOptions),
+            MenuItem::new("menu_main_menu", MenuAction::OpenMainMenu),
+        ],
+        1,
     );
 
-    game_over_text_element
-        .lines
-        .insert("game_over_title_line".to_string(), game_over_title_line);
+    let game_over_menu = Menu::new(
+        vec![
+            MenuItem::new("menu_play_again", MenuAction::PlayAgain),
+            MenuItem::new("menu_main_menu", MenuAction::OpenMainMenu),
+        ],
+        SUMMARY_LINES + 1,
+    );
 
-    text_elements.insert("pause_element".to_string(), pause_text_element);
-    text_elements.insert("game_over_element".to_string(), game_over_text_element);
-    text_elements.insert("score_element".to_string(), score_text_element);
-    text_elements.insert("mode_element".to_string(), mode_text_element);
-    text_elements
+    menus.insert("main_menu_element".to_string(), main_menu);
+    menus.insert("mode_select_element".to_string(), mode_select_menu);
+    menus.insert("options_element".to_string(), options_menu);
+    menus.insert("high_scores_element".to_string(), high_scores_menu);
+    menus.insert("pause_element".to_string(), pause_menu);
+    menus.insert("game_over_element".to_string(), game_over_menu);
+    menus
 }
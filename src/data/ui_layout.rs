@@ -0,0 +1,406 @@
+// Reads the text elements of every screen from a layout file, see assets/ui/game.ui
+use std::collections::HashMap;
+
+use crate::{
+    color::Color,
+    data::kv_file::{KvEntry, KvFile},
+    data_structs::Position2D,
+    entities::{
+        rich_text,
+        text_elements::{
+            Anchor, FontDefinition, FontName, TextAlignment, TextElement, TextLine, TextStyle,
+        },
+        text_template::TextTemplate,
+    },
+    error::GameError,
+    theme::parse_color,
+};
+
+pub fn load(path: &str) -> Result<HashMap<String, TextElement>, GameError> {
+    parse(&KvFile::load(path)?)
+}
+
+pub fn parse(file: &KvFile) -> Result<HashMap<String, TextElement>, GameError> {
+    let mut elements: HashMap<String, TextElement> = HashMap::new();
+
+    if let Some(entry) = file.section("").next() {
+        return Err(file.error(
+            entry,
+            &format!("`{}` has to be inside an [element] section", entry.key),
+        ));
+    }
+
+    // Elements first, so lines can come in any order after them
+    for (section, line) in &file.sections {
+        if section.contains('.') {
+            continue;
+        }
+        if elements.contains_key(section) {
+            return Err(GameError::Config(format!(
+                "{}:{line}: element [{section}] is defined twice",
+                file.path
+            )));
+        }
+        elements.insert(section.clone(), parse_element(file, section)?);
+    }
+
+    for (section, line) in &file.sections {
+        let Some((element_key, line_key)) = section.split_once('.') else {
+            continue;
+        };
+        let element = elements
+            .get_mut(element_key)
+            .ok_or(GameError::Config(format!(
+            "{}:{line}: line [{section}] belongs to element [{element_key}], which isn't defined",
+            file.path
+        )))?;
+        if element.lines.contains_key(line_key) {
+            return Err(GameError::Config(format!(
+                "{}:{line}: line [{section}] is defined twice",
+                file.path
+            )));
+        }
+        element
+            .lines
+            .insert(line_key.to_string(), parse_line(file, section)?);
+    }
+
+    check_parents(file, &elements)?;
+    Ok(elements)
+}
+
+// Every parent has to exist, and following parents must never lead back to the start
+fn check_parents(file: &KvFile, elements: &HashMap<String, TextElement>) -> Result<(), GameError> {
+    for (section, line) in &file.sections {
+        let mut current = section.as_str();
+        for _ in 0..elements.len() {
+            let Some(parent) = elements.get(current).and_then(|e| e.parent.as_deref()) else {
+                break;
+            };
+            if !elements.contains_key(parent) {
+                return Err(GameError::Config(format!(
+                    "{}:{line}: [{current}] has parent `{parent}`, which isn't defined",
+                    file.path
+                )));
+            }
+            if parent == section {
+                return Err(GameError::Config(format!(
+                    "{}:{line}: [{section}] ends up being its own parent",
+                    file.path
+                )));
+            }
+            current = parent;
+        }
+    }
+    Ok(())
+}
+
+fn parse_element(file: &KvFile, section: &str) -> Result<TextElement, GameError> {
+    let mut element = TextElement::new(0, Position2D::new(0, 0), Anchor::TopLeft, false);
+    for entry in file.section(section) {
+        match entry.key.as_str() {
+            "draw_order" => element.draw_order = parse_number(file, entry)?,
+            "position" => element.position = parse_position(file, entry)?,
+            "anchor" => {
+                element.anchor = Anchor::from_key(&entry.value).ok_or(file.error(
+                    entry,
+                    &format!(
+                        "unknown anchor `{}`, expected top_left, top, top_right, left, center, \
+                         right, bottom_left, bottom or bottom_right",
+                        entry.value
+                    ),
+                ))?
+            }
+            "parent" => element.parent = Some(entry.value.clone()),
+            "max_width" => element.max_width = Some(parse_number(file, entry)?),
+            "line_spacing" => element.line_spacing = parse_number(file, entry)?,
+            "padding" => element.padding = parse_number(file, entry)?,
+            "background" => element.background = Some(parse_color_entry(file, entry)?),
+            "overlay" => element.is_overlay = parse_bool(file, entry)?,
+            "visible" => element.visible = parse_bool(file, entry)?,
+            _ => return Err(unknown_key(file, entry, "an element")),
+        }
+    }
+    Ok(element)
+}
+
+fn parse_line(file: &KvFile, section: &str) -> Result<TextLine, GameError> {
+    let mut line = TextLine::new(
+        0,
+        Position2D::new(0, 0),
+        TextAlignment::Start,
+        FontDefinition::new(FontName::ArcadeNormal, 24, Color::WHITE),
+        String::new(),
+    );
+    for entry in file.section(section) {
+        match entry.key.as_str() {
+            "order" => line.vertical_order = parse_number(file, entry)?,
+            "position" => line.position = parse_position(file, entry)?,
+            "alignment" => line.alignment = parse_alignment(file, entry)?,
+            "font" => {
+                line.font.font_name = FontName::from_key(&entry.value).ok_or(file.error(
+                    entry,
+                    &format!(
+                    "unknown font `{}`, expected ArcadeInterlaced, ArcadeNormal or ArcadeRounded",
+                    entry.value
+                ),
+                ))?
+            }
+            "size" => line.font.font_size = parse_number(file, entry)?,
+            "color" => line.font.color = parse_color_entry(file, entry)?,
+            "style" => line.font.style = parse_style(file, entry)?,
+            "text" => {
+                rich_text::parse(&entry.value).map_err(|e| file.error(entry, &e))?;
+                line.text = entry.value.clone()
+            }
+            "message" => line.message = Some(entry.value.clone()),
+            "template" => {
+                line.template =
+                    Some(TextTemplate::parse(&entry.value).map_err(|e| file.error(entry, &e))?)
+            }
+            _ => return Err(unknown_key(file, entry, "a line")),
+        }
+    }
+    Ok(line)
+}
+
+fn parse_number<T: std::str::FromStr>(file: &KvFile, entry: &KvEntry) -> Result<T, GameError> {
+    entry.value.parse().map_err(|_| {
+        file.error(
+            entry,
+            &format!(
+                "`{}` expects a whole number, found `{}`",
+                entry.key, entry.value
+            ),
+        )
+    })
+}
+
+fn parse_position(file: &KvFile, entry: &KvEntry) -> Result<Position2D, GameError> {
+    let error = || {
+        file.error(
+            entry,
+            &format!("expected a position like `5, 10`, found `{}`", entry.value),
+        )
+    };
+    let (x, y) = entry.value.split_once(',').ok_or_else(error)?;
+    let x = x.trim().parse().map_err(|_| error())?;
+    let y = y.trim().parse().map_err(|_| error())?;
+    Ok(Position2D::new(x, y))
+}
+
+fn parse_color_entry(file: &KvFile, entry: &KvEntry) -> Result<Color, GameError> {
+    parse_color(&entry.value).ok_or(file.error(
+        entry,
+        &format!(
+            "expected a color like #RRGGBB or #RRGGBBAA, found `{}`",
+            entry.value
+        ),
+    ))
+}
+
+fn parse_alignment(file: &KvFile, entry: &KvEntry) -> Result<TextAlignment, GameError> {
+    match entry.value.as_str() {
+        "start" => Ok(TextAlignment::Start),
+        "center" => Ok(TextAlignment::Center),
+        "end" => Ok(TextAlignment::End),
+        other => Err(file.error(
+            entry,
+            &format!("unknown alignment `{other}`, expected start, center or end"),
+        )),
+    }
+}
+
+fn parse_bool(file: &KvFile, entry: &KvEntry) -> Result<bool, GameError> {
+    match entry.value.as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(file.error(
+            entry,
+            &format!("`{}` expects true or false, found `{other}`", entry.key),
+        )),
+    }
+}
+
+fn parse_style(file: &KvFile, entry: &KvEntry) -> Result<TextStyle, GameError> {
+    match entry.value.as_str() {
+        "fixed" => Ok(TextStyle::Fixed),
+        "title" => Ok(TextStyle::Title),
+        "body" => Ok(TextStyle::Body),
+        "hud" => Ok(TextStyle::Hud),
+        "highlight" => Ok(TextStyle::Highlight),
+        other => Err(file.error(
+            entry,
+            &format!("unknown style `{other}`, expected fixed, title, body, hud or highlight"),
+        )),
+    }
+}
+
+fn unknown_key(file: &KvFile, entry: &KvEntry, what: &str) -> GameError {
+    file.error(
+        entry,
+        &format!("`{}` isn't something {what} can have", entry.key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::UI_LAYOUT_PATH;
+
+    fn parse_source(source: &str) -> Result<HashMap<String, TextElement>, GameError> {
+        parse(&KvFile::parse(source, "test.ui")?)
+    }
+
+    fn parse_error(source: &str) -> String {
+        parse_source(source).err().unwrap().config_message()
+    }
+
+    #[test]
+    fn shipped_layout_loads() {
+        let elements = load(UI_LAYOUT_PATH).unwrap();
+        assert!(!elements.is_empty());
+    }
+
+    #[test]
+    fn reads_elements_and_their_lines() {
+        // Lines may come before the element they belong to
+        let source = "
+[hud.score]
+order = 1
+alignment = end
+font = ArcadeRounded
+size = 32
+color = #FF000080
+style = hud
+template = \"Score: {score}\"
+
+[hud]
+draw_order = 3
+anchor = top_right
+parent = frame
+position = 5, -10
+max_width = 400
+background = #000000C0
+visible = false
+
+[frame]
+";
+        let elements = parse_source(source).unwrap();
+        assert_eq!(elements.len(), 2);
+
+        let hud = &elements["hud"];
+        assert_eq!(hud.draw_order, 3);
+        assert!(hud.anchor == Anchor::TopRight);
+        assert_eq!(hud.parent.as_deref(), Some("frame"));
+        assert_eq!((hud.position.x, hud.position.y), (5, -10));
+        assert_eq!(hud.max_width, Some(400));
+        assert_eq!(hud.background, Some(Color::RGBA(0, 0, 0, 192)));
+        assert!(!hud.visible);
+
+        let score = &hud.lines["score"];
+        assert_eq!(score.vertical_order, 1);
+        assert!(matches!(score.alignment, TextAlignment::End));
+        assert!(score.font.font_name == FontName::ArcadeRounded);
+        assert_eq!(score.font.font_size, 32);
+        assert_eq!(score.font.color, Color::RGBA(255, 0, 0, 128));
+        assert!(score.font.style == TextStyle::Hud);
+        assert!(score.template.is_some());
+    }
+
+    #[test]
+    fn structure_mistakes_are_reported_with_their_line() {
+        assert_eq!(
+            parse_error("draw_order = 1\n[hud]"),
+            "test.ui:1: `draw_order` has to be inside an [element] section"
+        );
+        assert_eq!(
+            parse_error("[hud]\n[hud]"),
+            "test.ui:2: element [hud] is defined twice"
+        );
+        assert_eq!(
+            parse_error("[hud]\n[menu.title]"),
+            "test.ui:2: line [menu.title] belongs to element [menu], which isn't defined"
+        );
+        assert_eq!(
+            parse_error("[hud]\n[hud.score]\n[hud.score]"),
+            "test.ui:3: line [hud.score] is defined twice"
+        );
+    }
+
+    #[test]
+    fn parents_must_exist_and_not_loop() {
+        assert_eq!(
+            parse_error("[hud]\nparent = menu"),
+            "test.ui:1: [hud] has parent `menu`, which isn't defined"
+        );
+        assert_eq!(
+            parse_error("[a]\nparent = b\n[b]\nparent = c\n[c]\nparent = a"),
+            "test.ui:1: [a] ends up being its own parent"
+        );
+        assert_eq!(
+            parse_error("[a]\nparent = a"),
+            "test.ui:1: [a] ends up being its own parent"
+        );
+    }
+
+    #[test]
+    fn bad_values_are_reported_with_their_line() {
+        let errors = [
+            (
+                "[hud]\nsize = 3",
+                "test.ui:2: `size` isn't something an element can have",
+            ),
+            (
+                "[hud]\n[hud.a]\nparent = x",
+                "test.ui:3: `parent` isn't something a line can have",
+            ),
+            (
+                "[hud]\ndraw_order = -1",
+                "test.ui:2: `draw_order` expects a whole number, found `-1`",
+            ),
+            (
+                "[hud]\nposition = 5",
+                "test.ui:2: expected a position like `5, 10`, found `5`",
+            ),
+            (
+                "[hud]\nposition = a, 1",
+                "test.ui:2: expected a position like `5, 10`, found `a, 1`",
+            ),
+            (
+                "[hud]\nvisible = yes",
+                "test.ui:2: `visible` expects true or false, found `yes`",
+            ),
+            (
+                "[hud]\nbackground = red",
+                "test.ui:2: expected a color like #RRGGBB or #RRGGBBAA, found `red`",
+            ),
+            (
+                "[hud]\n[hud.a]\nalignment = left",
+                "test.ui:3: unknown alignment `left`, expected start, center or end",
+            ),
+            (
+                "[hud]\n[hud.a]\nstyle = bold",
+                "test.ui:3: unknown style `bold`, expected fixed, title, body, hud or highlight",
+            ),
+        ];
+        for (source, expected) in errors {
+            assert_eq!(parse_error(source), expected);
+        }
+        assert!(
+            parse_error("[hud]\nanchor = middle").starts_with("test.ui:2: unknown anchor `middle`")
+        );
+        assert!(parse_error("[hud]\n[hud.a]\nfont = Comic")
+            .starts_with("test.ui:3: unknown font `Comic`"));
+    }
+
+    #[test]
+    fn broken_markup_and_templates_are_reported() {
+        assert_eq!(
+            parse_error("[hud]\n[hud.a]\ntext = \"[color=red]3\""),
+            "test.ui:3: `[color]` is never closed in `[color=red]3`"
+        );
+        assert!(parse_error("[hud]\n[hud.a]\ntemplate = \"{lives}\"")
+            .starts_with("test.ui:3: unknown variable `{lives}`"));
+    }
+}
@@ -0,0 +1,163 @@
+use rand::Rng;
+
+use crate::{
+    constants::{GRID_X_SIZE, GRID_Y_SIZE},
+    entities::terrain::TerrainMap,
+    game_context::Point,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ActorKind {
+    BouncingBall,
+    PatrolBlock,
+    Mouse,
+}
+
+pub struct Actor {
+    pub kind: ActorKind,
+    pub position: Point,
+    pub velocity: Point,
+    pub move_interval: u32,
+    pub bonus: i32,
+    pub alive: bool,
+    ticks_since_move: u32,
+    patrol_length: u32,
+    patrol_steps: u32,
+}
+
+impl Actor {
+    pub fn new_bouncing_ball(position: Point, velocity: Point) -> Actor {
+        Actor::new(ActorKind::BouncingBall, position, velocity, 1, 0, 0)
+    }
+
+    pub fn new_patrol_block(position: Point, velocity: Point, patrol_length: u32) -> Actor {
+        Actor::new(
+            ActorKind::PatrolBlock,
+            position,
+            velocity,
+            2,
+            0,
+            patrol_length,
+        )
+    }
+
+    pub fn new_mouse(position: Point, bonus: i32) -> Actor {
+        Actor::new(ActorKind::Mouse, position, Point(0, 0), 2, bonus, 0)
+    }
+
+    fn new(
+        kind: ActorKind,
+        position: Point,
+        velocity: Point,
+        move_interval: u32,
+        bonus: i32,
+        patrol_length: u32,
+    ) -> Actor {
+        Actor {
+            kind,
+            position,
+            velocity,
+            move_interval,
+            bonus,
+            alive: true,
+            ticks_since_move: 0,
+            patrol_length,
+            patrol_steps: 0,
+        }
+    }
+
+    pub fn is_hazard(&self) -> bool {
+        !matches!(self.kind, ActorKind::Mouse)
+    }
+
+    pub fn update(&mut self, snake: &[Point], terrain: &TerrainMap) {
+        if !self.alive {
+            return;
+        }
+
+        self.ticks_since_move += 1;
+        if self.ticks_since_move < self.move_interval {
+            return;
+        }
+        self.ticks_since_move = 0;
+
+        match self.kind {
+            ActorKind::BouncingBall => self.bounce(terrain),
+            ActorKind::PatrolBlock => self.patrol(terrain),
+            ActorKind::Mouse => self.flee(snake, terrain),
+        }
+    }
+
+    // Only walls turn the ball, running into the snake is what makes it dangerous
+    fn bounce(&mut self, terrain: &TerrainMap) {
+        let Point(x, y) = self.position;
+        let Point(mut dx, mut dy) = self.velocity;
+        let blocked = |p: Point| !is_open(p, terrain);
+
+        if blocked(Point(x + dx, y)) {
+            dx = -dx;
+        }
+        if blocked(Point(x, y + dy)) {
+            dy = -dy;
+        }
+
+        self.velocity = Point(dx, dy);
+        let next = self.position + self.velocity;
+        if !blocked(next) {
+            self.position = next;
+        }
+    }
+
+    fn patrol(&mut self, terrain: &TerrainMap) {
+        let next = self.position + self.velocity;
+        if self.patrol_steps >= self.patrol_length || !is_open(next, terrain) {
+            self.velocity = Point(-self.velocity.0, -self.velocity.1);
+            self.patrol_steps = 0;
+        }
+        // Boxed in on both sides, wait until something opens up
+        let next = self.position + self.velocity;
+        if is_open(next, terrain) {
+            self.position = next;
+            self.patrol_steps += 1;
+        }
+    }
+
+    fn flee(&mut self, snake: &[Point], terrain: &TerrainMap) {
+        let head = match snake.first() {
+            Some(head) => *head,
+            None => return,
+        };
+
+        let mut candidates: Vec<Point> = [Point(0, -1), Point(0, 1), Point(-1, 0), Point(1, 0)]
+            .iter()
+            .map(|step| self.position + *step)
+            .filter(|p| is_open(*p, terrain) && !snake.contains(p))
+            .collect();
+        candidates.push(self.position);
+
+        // Mostly run away from the head, but wander now and then so it can be cornered
+        let mut rng = rand::thread_rng();
+        let next = if rng.gen_range(0..5) == 0 {
+            candidates[rng.gen_range(0..candidates.len())]
+        } else {
+            *candidates
+                .iter()
+                .max_by_key(|p| distance(**p, head))
+                .unwrap()
+        };
+        self.position = next;
+    }
+}
+
+// Cells actors can move onto, also where they may be placed
+pub fn is_open(point: Point, terrain: &TerrainMap) -> bool {
+    point.0 >= 0
+        && point.0 < GRID_X_SIZE as i32
+        && point.1 >= 0
+        && point.1 < GRID_Y_SIZE as i32
+        && !terrain.is_solid(point)
+}
+
+fn distance(a: Point, b: Point) -> i32 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
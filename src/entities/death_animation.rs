@@ -0,0 +1,84 @@
+use rand::Rng;
+
+use crate::{game_context::Point, settings::DeathSequence};
+
+pub struct ScatteredSegment {
+    pub x: f32,
+    pub y: f32,
+    velocity_x: f32,
+    velocity_y: f32,
+}
+
+pub struct DeathAnimation {
+    pub frame: u32,
+    pub segments: Vec<ScatteredSegment>,
+    sequence: DeathSequence,
+}
+
+impl DeathAnimation {
+    pub fn new(snake: &[Point], sequence: DeathSequence) -> DeathAnimation {
+        let mut rng = rand::thread_rng();
+        let segments = snake
+            .iter()
+            .map(|Point(x, y)| ScatteredSegment {
+                x: *x as f32,
+                y: *y as f32,
+                velocity_x: rng.gen_range(-1.0..1.0) * sequence.scatter_speed,
+                velocity_y: rng.gen_range(-1.0..1.0) * sequence.scatter_speed,
+            })
+            .collect();
+
+        DeathAnimation {
+            frame: 0,
+            segments,
+            sequence,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.frame += 1;
+        if self.is_scattering() {
+            for segment in &mut self.segments {
+                segment.x += segment.velocity_x;
+                segment.y += segment.velocity_y;
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.sequence.flash_frames + self.sequence.fade_frames
+    }
+
+    // Segments hold still while the screen flashes, then fly apart
+    pub fn is_scattering(&self) -> bool {
+        self.frame >= self.sequence.flash_frames
+    }
+
+    pub fn is_flash_on(&self) -> bool {
+        self.sequence.flashing && !self.is_scattering() && (self.frame / 4).is_multiple_of(2)
+    }
+
+    pub fn segment_alpha(&self) -> u8 {
+        if !self.is_scattering() || self.sequence.fade_frames == 0 {
+            return 255;
+        }
+        let faded = self.frame - self.sequence.flash_frames;
+        let remaining = self.sequence.fade_frames.saturating_sub(faded);
+        (remaining * 255 / self.sequence.fade_frames) as u8
+    }
+
+    // Pixel offset applied to the whole board, decaying over the shake duration
+    pub fn shake_offset(&self) -> (i32, i32) {
+        if self.frame >= self.sequence.shake_frames {
+            return (0, 0);
+        }
+        let remaining = (self.sequence.shake_frames - self.frame) as f32;
+        let strength =
+            self.sequence.shake_intensity as f32 * remaining / self.sequence.shake_frames as f32;
+        let mut rng = rand::thread_rng();
+        (
+            (rng.gen_range(-1.0..1.0) * strength) as i32,
+            (rng.gen_range(-1.0..1.0) * strength) as i32,
+        )
+    }
+}
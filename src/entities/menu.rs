@@ -0,0 +1,149 @@
+use crate::{
+    color::Color,
+    data_structs::Position2D,
+    entities::text_elements::{
+        FontDefinition, FontName, TextAlignment, TextElement, TextLine, TextStyle,
+    },
+    game_context::GameMode,
+    localization::Localization,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    OpenMainMenu,
+    OpenModeSelect,
+    OpenOptions,
+    OpenHighScores,
+    StartGame(GameMode),
+    PlayAgain,
+    Resume,
+    CycleSpeed,
+    CycleTheme,
+    CycleColorPreset,
+    ToggleShapeMarkers,
+    ToggleReducedMotion,
+    ToggleSmoothMovement,
+    CycleEffects,
+    CycleLanguage,
+    // Steps a volume by the amount, left goes the other way
+    ChangeVolume(VolumeChannel, i8),
+    ToggleMute,
+    Back,
+    Quit,
+}
+
+impl MenuAction {
+    // Settings change in place and can also be cycled with left and right
+    pub fn is_setting(&self) -> bool {
+        matches!(
+            *self,
+            MenuAction::CycleSpeed
+                | MenuAction::CycleTheme
+                | MenuAction::CycleColorPreset
+                | MenuAction::ToggleShapeMarkers
+                | MenuAction::ToggleReducedMotion
+                | MenuAction::ToggleSmoothMovement
+                | MenuAction::CycleEffects
+                | MenuAction::CycleLanguage
+                | MenuAction::ChangeVolume(..)
+                | MenuAction::ToggleMute
+        )
+    }
+
+    // What pressing left does with the setting, only sliders move backwards
+    pub fn reversed(&self) -> MenuAction {
+        match *self {
+            MenuAction::ChangeVolume(channel, step) => MenuAction::ChangeVolume(channel, -step),
+            action => action,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum VolumeChannel {
+    Music,
+    Sound,
+}
+
+pub struct MenuItem {
+    // Catalog key of the label, settings replace the label with their current value
+    pub message: String,
+    pub label: String,
+    pub action: MenuAction,
+}
+
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+    pub selected: usize,
+    // Vertical order of the first item line, so items go below the element's own lines
+    pub first_line_order: u32,
+}
+
+impl MenuItem {
+    pub fn new(message: &str, action: MenuAction) -> MenuItem {
+        MenuItem {
+            message: message.to_string(),
+            label: message.to_string(),
+            action,
+        }
+    }
+}
+
+impl Menu {
+    pub fn new(items: Vec<MenuItem>, first_line_order: u32) -> Menu {
+        Menu {
+            items,
+            selected: 0,
+            first_line_order,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn selected_action(&self) -> Option<MenuAction> {
+        self.items.get(self.selected).map(|item| item.action)
+    }
+
+    pub fn localize(&mut self, localization: &Localization) {
+        for item in &mut self.items {
+            item.label = localization.get(&item.message).to_string();
+        }
+    }
+
+    // Write the items into the element as lines, highlighting the selected one
+    pub fn apply_to(&self, element: &mut TextElement) {
+        element
+            .lines
+            .retain(|key, _| !key.starts_with("menu_item_"));
+
+        for (i, item) in self.items.iter().enumerate() {
+            let (text, color, style) = if i == self.selected {
+                (
+                    format!("> {} <", item.label),
+                    Color::YELLOW,
+                    TextStyle::Highlight,
+                )
+            } else {
+                (item.label.clone(), Color::WHITE, TextStyle::Body)
+            };
+            let line = TextLine::new(
+                self.first_line_order + i as u32,
+                Position2D::new(0, 0),
+                TextAlignment::Center,
+                FontDefinition::new(FontName::ArcadeRounded, 24, color).with_style(style),
+                text,
+            );
+            element.lines.insert(format!("menu_item_{i}"), line);
+        }
+    }
+}
@@ -1 +1,8 @@
+pub mod actors;
+pub mod death_animation;
+pub mod menu;
+pub mod rich_text;
+pub mod terrain;
 pub mod text_elements;
+pub mod text_template;
+pub mod tween;
@@ -0,0 +1,284 @@
+use crate::color::Color;
+use crate::entities::text_elements::{FontName, TextStyle};
+use crate::theme::parse_color;
+
+// Markup for styling parts of a line:
+//   [color=red]3[/color]     a named color, #RRGGBB, or the theme color of title, body,
+//                            hud or highlight; `default` keeps the line's color
+//   [font=ArcadeRounded]..[/font]
+//   [size=32]..[/size]
+//   [key:Esc]                a key name drawn as a keycap
+//   [[                       a literal `[`
+// Tags can be nested and every tag has to be closed.
+
+#[derive(Copy, Clone, PartialEq, Default)]
+pub enum RunColor {
+    // Whatever color the line has
+    #[default]
+    Line,
+    // The theme's color for a style
+    Role(TextStyle),
+    Fixed(Color),
+}
+
+// Changes a run makes to the line's font, anything left as None comes from the line
+#[derive(Copy, Clone, PartialEq, Default)]
+pub struct RunStyle {
+    pub color: RunColor,
+    pub font_name: Option<FontName>,
+    pub font_size: Option<u16>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub style: RunStyle,
+    pub is_key: bool,
+}
+
+const NAMED_COLORS: [(&str, Color); 8] = [
+    ("white", Color::RGB(255, 255, 255)),
+    ("black", Color::RGB(0, 0, 0)),
+    ("red", Color::RGB(255, 64, 64)),
+    ("green", Color::RGB(64, 220, 64)),
+    ("blue", Color::RGB(80, 140, 255)),
+    ("yellow", Color::RGB(255, 255, 0)),
+    ("orange", Color::RGB(255, 160, 0)),
+    ("grey", Color::RGB(160, 160, 160)),
+];
+
+impl TextRun {
+    pub fn plain(text: &str) -> TextRun {
+        TextRun {
+            text: text.to_string(),
+            style: RunStyle::default(),
+            is_key: false,
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Vec<TextRun>, String> {
+    let mut runs = Vec::new();
+    let mut colors: Vec<RunColor> = Vec::new();
+    let mut fonts: Vec<FontName> = Vec::new();
+    let mut sizes: Vec<u16> = Vec::new();
+    let mut text = String::new();
+
+    let mut rest = source;
+    while let Some(start) = rest.find('[') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("[[") {
+            text.push('[');
+            rest = after;
+            continue;
+        }
+        let end = rest
+            .find(']')
+            .ok_or(format!("`[` without a closing `]` in `{source}`"))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        // Every tag ends the run before it
+        let style = RunStyle {
+            color: colors.last().copied().unwrap_or_default(),
+            font_name: fonts.last().copied(),
+            font_size: sizes.last().copied(),
+        };
+        push_run(&mut runs, std::mem::take(&mut text), style, false);
+
+        if let Some(key) = tag.strip_prefix("key:") {
+            push_run(&mut runs, key.to_string(), style, true);
+            continue;
+        }
+        match tag.split_once('=') {
+            Some(("color", value)) => colors.push(parse_run_color(value)?),
+            Some(("font", value)) => fonts.push(
+                FontName::from_key(value).ok_or(format!("unknown font `{value}` in `[{tag}]`"))?,
+            ),
+            Some(("size", value)) => sizes.push(
+                value
+                    .parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .ok_or(format!("`[size]` expects a whole number, found `{value}`"))?,
+            ),
+            _ => {
+                let closed = match tag {
+                    "/color" => colors.pop().is_some(),
+                    "/font" => fonts.pop().is_some(),
+                    "/size" => sizes.pop().is_some(),
+                    _ => {
+                        return Err(format!(
+                            "unknown tag `[{tag}]`, expected color, font, size or key"
+                        ))
+                    }
+                };
+                if !closed {
+                    return Err(format!("`[{tag}]` without an opening tag in `{source}`"));
+                }
+            }
+        }
+    }
+    text.push_str(rest);
+
+    for (name, open) in [
+        ("color", colors.len()),
+        ("font", fonts.len()),
+        ("size", sizes.len()),
+    ] {
+        if open > 0 {
+            return Err(format!("`[{name}]` is never closed in `{source}`"));
+        }
+    }
+    push_run(&mut runs, text, RunStyle::default(), false);
+    Ok(runs)
+}
+
+// The text without markup, for frontends that can't style runs
+pub fn plain_text(source: &str) -> String {
+    match parse(source) {
+        Ok(runs) => runs
+            .iter()
+            .map(|run| {
+                if run.is_key {
+                    format!("[{}]", run.text)
+                } else {
+                    run.text.clone()
+                }
+            })
+            .collect(),
+        Err(_) => source.to_string(),
+    }
+}
+
+fn push_run(runs: &mut Vec<TextRun>, text: String, style: RunStyle, is_key: bool) {
+    if text.is_empty() {
+        return;
+    }
+    match runs.last_mut() {
+        Some(last) if !is_key && !last.is_key && last.style == style => last.text.push_str(&text),
+        _ => runs.push(TextRun {
+            text,
+            style,
+            is_key,
+        }),
+    }
+}
+
+fn parse_run_color(value: &str) -> Result<RunColor, String> {
+    let role = match value {
+        "default" => return Ok(RunColor::Line),
+        "title" => Some(TextStyle::Title),
+        "body" => Some(TextStyle::Body),
+        "hud" => Some(TextStyle::Hud),
+        "highlight" => Some(TextStyle::Highlight),
+        _ => None,
+    };
+    if let Some(role) = role {
+        return Ok(RunColor::Role(role));
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, color)| *color)
+        .or_else(|| parse_color(value))
+        .map(RunColor::Fixed)
+        .ok_or_else(|| {
+            let names: Vec<&str> = NAMED_COLORS.iter().map(|(name, _)| *name).collect();
+            format!(
+                "unknown color `{value}`, expected #RRGGBB, default, title, body, hud, highlight or {}",
+                names.join(", ")
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(runs: &[TextRun]) -> Vec<&str> {
+        runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    #[test]
+    fn plain_text_is_one_run() {
+        let runs = parse("Press Enter").unwrap();
+        assert!(runs == vec![TextRun::plain("Press Enter")]);
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn tags_split_the_line_into_styled_runs() {
+        let runs = parse("Score [color=red]3[/color] of [size=32]10[/size]").unwrap();
+        assert_eq!(texts(&runs), ["Score ", "3", " of ", "10"]);
+        assert!(runs[0].style == RunStyle::default());
+        assert!(runs[1].style.color == RunColor::Fixed(Color::RGB(255, 64, 64)));
+        assert!(runs[2].style == RunStyle::default());
+        assert_eq!(runs[3].style.font_size, Some(32));
+    }
+
+    #[test]
+    fn nested_tags_combine_and_unwind() {
+        let runs =
+            parse("[font=ArcadeRounded][color=#00FF00]a[color=highlight]b[/color]c[/color][/font]")
+                .unwrap();
+        assert_eq!(texts(&runs), ["a", "b", "c"]);
+        for run in &runs {
+            assert!(run.style.font_name == Some(FontName::ArcadeRounded));
+        }
+        assert!(runs[0].style.color == RunColor::Fixed(Color::RGB(0, 255, 0)));
+        assert!(runs[1].style.color == RunColor::Role(TextStyle::Highlight));
+        assert!(runs[2].style.color == runs[0].style.color);
+
+        let runs = parse("[color=red]a[color=default]b[/color][/color]").unwrap();
+        assert!(runs[1].style.color == RunColor::Line);
+    }
+
+    #[test]
+    fn keys_and_escaped_brackets() {
+        let runs = parse("Press [key:Esc] to go back [[or not]").unwrap();
+        assert_eq!(texts(&runs), ["Press ", "Esc", " to go back [or not]"]);
+        assert!(runs[1].is_key && !runs[0].is_key && !runs[2].is_key);
+
+        // Keys next to each other stay apart
+        let runs = parse("[key:A][key:B]").unwrap();
+        assert_eq!(texts(&runs), ["A", "B"]);
+    }
+
+    #[test]
+    fn plain_text_drops_markup() {
+        assert_eq!(
+            plain_text("[color=red]Press[/color] [key:Esc] [[1]"),
+            "Press [Esc] [1]"
+        );
+        // Broken markup is shown as it is
+        assert_eq!(plain_text("[color=red]oops"), "[color=red]oops");
+    }
+
+    #[test]
+    fn rejects_broken_markup() {
+        let errors = [
+            ("[color=red", "`[` without a closing `]` in `[color=red`"),
+            (
+                "[color=red]a",
+                "`[color]` is never closed in `[color=red]a`",
+            ),
+            ("a[/size]", "`[/size]` without an opening tag in `a[/size]`"),
+            (
+                "[bold]a",
+                "unknown tag `[bold]`, expected color, font, size or key",
+            ),
+            ("[font=Comic]", "unknown font `Comic` in `[font=Comic]`"),
+            ("[size=0]", "`[size]` expects a whole number, found `0`"),
+            ("[size=big]", "`[size]` expects a whole number, found `big`"),
+        ];
+        for (source, expected) in errors {
+            assert_eq!(parse(source).err().unwrap(), expected);
+        }
+        assert!(parse("[color=pink]")
+            .err()
+            .unwrap()
+            .starts_with("unknown color `pink`"));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    constants::{GRID_X_SIZE, GRID_Y_SIZE},
+    game_context::{PlayerDirection, Point},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TerrainTile {
+    Floor,
+    Ice,
+    Conveyor(PlayerDirection),
+    Mud,
+    OneWayGate(PlayerDirection),
+    BreakableWall,
+}
+
+pub struct TerrainMap {
+    tiles: Vec<TerrainTile>,
+}
+
+impl Default for TerrainMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerrainMap {
+    pub fn new() -> TerrainMap {
+        TerrainMap {
+            tiles: vec![TerrainTile::Floor; (GRID_X_SIZE * GRID_Y_SIZE) as usize],
+        }
+    }
+
+    pub fn get(&self, point: Point) -> TerrainTile {
+        match TerrainMap::index(point) {
+            Some(i) => self.tiles[i],
+            None => TerrainTile::Floor,
+        }
+    }
+
+    pub fn set(&mut self, point: Point, tile: TerrainTile) {
+        if let Some(i) = TerrainMap::index(point) {
+            self.tiles[i] = tile;
+        }
+    }
+
+    pub fn fill(&mut self, from: Point, to: Point, tile: TerrainTile) {
+        for y in from.1..=to.1 {
+            for x in from.0..=to.0 {
+                self.set(Point(x, y), tile);
+            }
+        }
+    }
+
+    pub fn is_solid(&self, point: Point) -> bool {
+        matches!(self.get(point), TerrainTile::BreakableWall)
+    }
+
+    // Every tile that isn't plain floor, for drawing and spawn exclusion
+    pub fn tiles(&self) -> impl Iterator<Item = (Point, TerrainTile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| !matches!(tile, TerrainTile::Floor))
+            .map(|(i, tile)| {
+                let x = i as i32 % GRID_X_SIZE as i32;
+                let y = i as i32 / GRID_X_SIZE as i32;
+                (Point(x, y), *tile)
+            })
+    }
+
+    fn index(point: Point) -> Option<usize> {
+        let Point(x, y) = point;
+        if x < 0 || y < 0 || x >= GRID_X_SIZE as i32 || y >= GRID_Y_SIZE as i32 {
+            return None;
+        }
+        Some((y * GRID_X_SIZE as i32 + x) as usize)
+    }
+}
@@ -1,26 +1,98 @@
-extern crate sdl2;
 use std::collections::HashMap;
 
-use sdl2::pixels::Color;
-
+use crate::color::Color;
 use crate::data_structs::Position2D;
+use crate::entities::text_template::{GameVariables, TextTemplate};
+use crate::entities::tween::Tween;
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum FontName {
     ArcadeInterlaced,
     ArcadeNormal,
     ArcadeRounded,
 }
 
+impl FontName {
+    pub fn from_key(key: &str) -> Option<FontName> {
+        match key {
+            "ArcadeInterlaced" => Some(FontName::ArcadeInterlaced),
+            "ArcadeNormal" => Some(FontName::ArcadeNormal),
+            "ArcadeRounded" => Some(FontName::ArcadeRounded),
+            _ => None,
+        }
+    }
+}
+
+// Role of a line in the UI, the active theme picks font and color for every role but Fixed
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum TextStyle {
+    Fixed,
+    Title,
+    Body,
+    Hud,
+    Highlight,
+}
+
+// Horizontal placement of a line inside its element's box
 pub enum TextAlignment {
     Start,
     Center,
     End,
 }
 
+// Point of the screen, or of the parent element, that an element is attached to. The same
+// point of the element's own box is put there.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub fn from_key(key: &str) -> Option<Anchor> {
+        match key {
+            "top_left" => Some(Anchor::TopLeft),
+            "top" => Some(Anchor::Top),
+            "top_right" => Some(Anchor::TopRight),
+            "left" => Some(Anchor::Left),
+            "center" => Some(Anchor::Center),
+            "right" => Some(Anchor::Right),
+            "bottom_left" => Some(Anchor::BottomLeft),
+            "bottom" => Some(Anchor::Bottom),
+            "bottom_right" => Some(Anchor::BottomRight),
+            _ => None,
+        }
+    }
+
+    // How far across and down the box the anchor sits, 0.0 to 1.0
+    pub fn fraction(&self) -> (f32, f32) {
+        match *self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
 pub struct FontDefinition {
     pub font_name: FontName,
     pub font_size: u16,
     pub color: Color,
+    pub style: TextStyle,
 }
 
 pub struct TextLine {
@@ -29,15 +101,39 @@ pub struct TextLine {
     pub alignment: TextAlignment,
     pub font: FontDefinition,
     pub text: String,
+    // Replaces `text` with game values every frame when set
+    pub template: Option<TextTemplate>,
+    // Catalog key `text` is looked up from whenever the language changes
+    pub message: Option<String>,
+}
+
+// Tweens played on a whole element, anything without one stays at rest
+#[derive(Default)]
+pub struct TextAnimation {
+    pub offset_x: Option<Tween>,
+    pub offset_y: Option<Tween>,
+    pub alpha: Option<Tween>,
+    pub scale: Option<Tween>,
+    // Drop the element once every tween is done, for one-off popups
+    pub remove_when_finished: bool,
 }
 
 pub struct TextElement {
     pub draw_order: u32,
     pub visible: bool,
+    // Offset from the anchor, positive values move away from the edge it's anchored to
     pub position: Position2D,
-    pub alignment: TextAlignment,
+    pub anchor: Anchor,
+    // Key of the element this one is anchored to instead of the screen
+    pub parent: Option<String>,
+    // Lines longer than this are wrapped at spaces
+    pub max_width: Option<u32>,
+    pub line_spacing: i32,
+    pub padding: i32,
+    pub background: Option<Color>,
     pub lines: HashMap<String, TextLine>,
     pub is_overlay: bool,
+    pub animation: TextAnimation,
 }
 
 impl FontDefinition {
@@ -46,8 +142,51 @@ impl FontDefinition {
             font_name,
             font_size,
             color,
+            style: TextStyle::Fixed,
         }
     }
+
+    pub fn with_style(mut self, style: TextStyle) -> FontDefinition {
+        self.style = style;
+        self
+    }
+}
+
+impl TextAnimation {
+    pub fn update(&mut self) {
+        for tween in [
+            &mut self.offset_x,
+            &mut self.offset_y,
+            &mut self.alpha,
+            &mut self.scale,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            tween.update();
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        [self.offset_x, self.offset_y, self.alpha, self.scale]
+            .iter()
+            .flatten()
+            .all(|tween| tween.is_finished())
+    }
+
+    pub fn offset(&self) -> (i32, i32) {
+        let value = |tween: Option<Tween>| tween.map_or(0, |t| t.value().round() as i32);
+        (value(self.offset_x), value(self.offset_y))
+    }
+
+    pub fn alpha(&self) -> u8 {
+        self.alpha
+            .map_or(255, |t| t.value().clamp(0.0, 255.0) as u8)
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale.map_or(1.0, |t| t.value())
+    }
 }
 
 impl TextLine {
@@ -64,6 +203,17 @@ impl TextLine {
             alignment,
             font,
             text,
+            template: None,
+            message: None,
+        }
+    }
+}
+
+impl TextLine {
+    pub fn resolved_text(&self, variables: &GameVariables) -> String {
+        match &self.template {
+            Some(template) => template.render(variables),
+            None => self.text.clone(),
         }
     }
 }
@@ -72,16 +222,22 @@ impl TextElement {
     pub fn new(
         draw_order: u32,
         position: Position2D,
-        alignment: TextAlignment,
+        anchor: Anchor,
         is_overlay: bool,
     ) -> TextElement {
         TextElement {
             draw_order,
             visible: true,
             position,
-            alignment,
+            anchor,
+            parent: None,
+            max_width: None,
+            line_spacing: 0,
+            padding: 0,
+            background: None,
             lines: HashMap::new(),
             is_overlay,
+            animation: TextAnimation::default(),
         }
     }
 }
@@ -0,0 +1,178 @@
+use std::fmt::Write;
+
+// Values a text template can show, named in templates as `{score}`, `{mode}` and so on
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum GameVariable {
+    Score,
+    Mode,
+    Countdown,
+    Length,
+    Speed,
+    Best,
+    FoodEaten,
+    TimeAlive,
+    ScoreColor,
+}
+
+const VARIABLES: [(&str, GameVariable); 9] = [
+    ("score", GameVariable::Score),
+    ("mode", GameVariable::Mode),
+    ("countdown", GameVariable::Countdown),
+    ("length", GameVariable::Length),
+    ("speed", GameVariable::Speed),
+    ("best", GameVariable::Best),
+    ("food_eaten", GameVariable::FoodEaten),
+    ("time_alive", GameVariable::TimeAlive),
+    ("score_color", GameVariable::ScoreColor),
+];
+
+impl GameVariable {
+    pub fn from_key(key: &str) -> Option<GameVariable> {
+        VARIABLES
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, variable)| *variable)
+    }
+}
+
+// Snapshot of the game taken once per frame for resolving templates, names are already
+// in the selected language
+pub struct GameVariables {
+    pub score: i32,
+    pub mode: String,
+    pub countdown: u32,
+    pub length: usize,
+    pub speed: String,
+    // Top saved score, a run only adds to it at game over so `{best}` shows the score while
+    // it is higher
+    pub best: i32,
+    pub food_eaten: u32,
+    pub time_alive: String,
+}
+
+impl GameVariables {
+    fn write(&self, variable: GameVariable, out: &mut String) {
+        let _ = match variable {
+            GameVariable::Score => write!(out, "{}", self.score),
+            GameVariable::Mode => write!(out, "{}", self.mode),
+            GameVariable::Countdown => write!(out, "{}", self.countdown),
+            GameVariable::Length => write!(out, "{}", self.length),
+            GameVariable::Speed => write!(out, "{}", self.speed),
+            GameVariable::Best => write!(out, "{}", self.score.max(self.best)),
+            GameVariable::FoodEaten => write!(out, "{}", self.food_eaten),
+            GameVariable::TimeAlive => write!(out, "{}", self.time_alive),
+            // For markup, `[color={score_color}]` lights the score up once it beats the saved best
+            GameVariable::ScoreColor => {
+                let beating_best = self.score > 0 && self.score > self.best;
+                out.write_str(if beating_best { "highlight" } else { "default" })
+            }
+        };
+    }
+}
+
+enum TemplatePart {
+    Literal(String),
+    Variable(GameVariable),
+}
+
+// Text with `{variable}` placeholders, checked when it's parsed so drawing can't fail
+pub struct TextTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl TextTemplate {
+    pub fn parse(source: &str) -> Result<TextTemplate, String> {
+        let mut parts = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or(format!("`{{` without a closing `}}` in `{source}`"))?;
+            let name = &rest[start + 1..start + end];
+            let variable = GameVariable::from_key(name).ok_or_else(|| {
+                let known: Vec<&str> = VARIABLES.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown variable `{{{name}}}`, expected one of {}",
+                    known.join(", ")
+                )
+            })?;
+            parts.push(TemplatePart::Variable(variable));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+        Ok(TextTemplate { parts })
+    }
+
+    pub fn render(&self, variables: &GameVariables) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => out.push_str(text),
+                TemplatePart::Variable(variable) => variables.write(*variable, &mut out),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> GameVariables {
+        GameVariables {
+            score: 12,
+            mode: String::from("Classic"),
+            countdown: 3,
+            length: 15,
+            speed: String::from("Fast"),
+            best: 20,
+            food_eaten: 12,
+            time_alive: String::from("1:05"),
+        }
+    }
+
+    fn render(source: &str, variables: &GameVariables) -> String {
+        TextTemplate::parse(source).unwrap().render(variables)
+    }
+
+    #[test]
+    fn fills_in_every_variable() {
+        let source = "{score} {mode} {countdown} {length} {speed} {best} {food_eaten} {time_alive}";
+        assert_eq!(
+            render(source, &variables()),
+            "12 Classic 3 15 Fast 20 12 1:05"
+        );
+    }
+
+    #[test]
+    fn keeps_the_text_around_variables() {
+        assert_eq!(render("Score: {score}!", &variables()), "Score: 12!");
+        assert_eq!(render("{score}{best}", &variables()), "1220");
+        assert_eq!(render("No variables", &variables()), "No variables");
+        assert_eq!(render("", &variables()), "");
+    }
+
+    #[test]
+    fn best_shows_the_score_once_it_is_higher() {
+        let mut variables = variables();
+        variables.score = 25;
+        assert_eq!(render("{best}", &variables), "25");
+    }
+
+    #[test]
+    fn rejects_unknown_and_unclosed_variables() {
+        let error = TextTemplate::parse("Lives: {lives}").err().unwrap();
+        assert!(error.starts_with("unknown variable `{lives}`, expected one of score, mode"));
+        assert_eq!(
+            TextTemplate::parse("Score: {score").err().unwrap(),
+            "`{` without a closing `}` in `Score: {score`"
+        );
+        assert!(TextTemplate::parse("{}").is_err());
+    }
+}
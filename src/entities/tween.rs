@@ -0,0 +1,51 @@
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseOut,
+    // Out to `to` and back to `from` over the duration
+    PingPong,
+}
+
+// A value moving from one number to another over a number of frames
+#[derive(Copy, Clone)]
+pub struct Tween {
+    pub from: f32,
+    pub to: f32,
+    frame: u32,
+    duration: u32,
+    easing: Easing,
+}
+
+impl Tween {
+    pub fn new(from: f32, to: f32, duration: u32, easing: Easing) -> Tween {
+        Tween {
+            from,
+            to,
+            frame: 0,
+            duration,
+            easing,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.frame = (self.frame + 1).min(self.duration);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.duration
+    }
+
+    pub fn value(&self) -> f32 {
+        let t = if self.duration == 0 {
+            1.0
+        } else {
+            self.frame as f32 / self.duration as f32
+        };
+        let eased = match self.easing {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::PingPong => 1.0 - (2.0 * t - 1.0).abs(),
+        };
+        self.from + (self.to - self.from) * eased
+    }
+}
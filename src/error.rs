@@ -0,0 +1,48 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+// Everything that can stop the game, the message is shown to the player before it exits
+#[derive(Debug)]
+pub enum GameError {
+    // SDL or one of its subsystems couldn't start
+    SdlInit(String),
+    Render(String),
+    Font { path: PathBuf, message: String },
+    Asset { path: PathBuf, message: String },
+    // A mistake in a layout, theme or language file, the message names the file and line
+    Config(String),
+    // High scores, screenshots and anything else the game writes for the player
+    SaveFile { path: PathBuf, message: String },
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameError::SdlInit(message) => write!(f, "Could not start SDL: {message}"),
+            GameError::Render(message) => write!(f, "Drawing failed: {message}"),
+            GameError::Font { path, message } => {
+                write!(f, "Could not load font {}: {message}", path.display())
+            }
+            GameError::Asset { path, message } => {
+                write!(f, "Could not load {}: {message}", path.display())
+            }
+            GameError::Config(message) => write!(f, "{message}"),
+            GameError::SaveFile { path, message } => {
+                write!(f, "Problem with {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[cfg(test)]
+impl GameError {
+    // Message of a mistake in a file, tests of the file formats compare these
+    pub fn config_message(self) -> String {
+        match self {
+            GameError::Config(message) => message,
+            other => panic!("expected a mistake in a file, got: {other}"),
+        }
+    }
+}
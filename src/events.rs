@@ -0,0 +1,56 @@
+use crate::{
+    game_context::{GameContext, GameMode, GameState, PlayerDirection, Point},
+    stats::DeathCause,
+};
+
+// Things that happened during a frame, collected by GameContext for the frontends to react to
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    FoodEaten {
+        at: Point,
+    },
+    // The snake got longer, `length` counts the new segment
+    Grew {
+        length: usize,
+    },
+    PowerUpCollected {
+        at: Point,
+    },
+    // The head left the board at `exit` and came back in at `entry`
+    Wrapped {
+        exit: Point,
+        entry: Point,
+    },
+    Died {
+        cause: DeathCause,
+        segments: Vec<Point>,
+    },
+    // The score made it onto the high score table
+    HighScore {
+        score: i32,
+        mode: GameMode,
+    },
+    // The snake's heading changed, turns queued during the countdown count once they're made
+    Turned {
+        direction: PlayerDirection,
+    },
+    ModeChanged {
+        mode: GameMode,
+    },
+    Paused,
+    Resumed,
+    // Another screen is on top, also sent for the first screen
+    ScreenChanged {
+        state: GameState,
+    },
+    MenuMoved,
+    // A menu item was chosen or a setting changed
+    MenuSelected,
+    MenuBack,
+}
+
+// Anything that reacts to the game's events. Every subscriber gets the same events each
+// frame, after the update that produced them, and ignores the ones it has no use for.
+pub trait EventSubscriber {
+    fn handle_events(&mut self, events: &[GameEvent], context: &GameContext);
+}
@@ -1,39 +1,118 @@
-extern crate sdl2;
-
 use crate::{
-    constants::{GRID_X_SIZE, GRID_Y_SIZE},
-    data::text_data,
-    entities::text_elements::TextElement,
+    assets,
+    color::Color,
+    constants::{
+        COUNTDOWN_FRAMES, DOT_SIZE_IN_PXS, FRAMES_PER_SECOND, GRID_X_SIZE, GRID_Y_SIZE,
+        HIGH_SCORES_PATH, I18N_PATH, MAX_BUFFERED_TURNS, THEMES_PATH, TRANSITION_FRAMES,
+        UI_LAYOUT_PATH,
+    },
+    data::{actor_data, terrain_data, text_data, ui_layout},
+    data_structs::Position2D,
+    entities::{
+        actors::{Actor, ActorKind},
+        death_animation::DeathAnimation,
+        menu::{Menu, MenuAction, VolumeChannel},
+        terrain::{TerrainMap, TerrainTile},
+        text_elements::{
+            Anchor, FontDefinition, FontName, TextAlignment, TextAnimation, TextElement, TextLine,
+            TextStyle,
+        },
+        text_template::GameVariables,
+        tween::{Easing, Tween},
+    },
+    events::{EventSubscriber, GameEvent},
+    high_scores::HighScores,
+    input::InputAction,
+    localization::Localization,
+    settings::{Settings, VOLUME_STEPS},
+    stats::{DeathCause, RunStats},
+    theme::Theme,
 };
 use rand::Rng;
-use std::{collections::HashMap, fmt::Display, ops::Add};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    ops::Add,
+    path::PathBuf,
+    time::SystemTime,
+};
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum GameState {
+    Title,
+    MainMenu,
+    ModeSelect,
+    Options,
+    HighScores,
     Playing,
+    Dying,
     Paused,
     Over,
 }
+
+impl GameState {
+    // Key of the text element shown while this screen is on top
+    fn element_key(&self) -> Option<&'static str> {
+        match *self {
+            GameState::Title => Some("title_element"),
+            GameState::MainMenu => Some("main_menu_element"),
+            GameState::ModeSelect => Some("mode_select_element"),
+            GameState::Options => Some("options_element"),
+            GameState::HighScores => Some("high_scores_element"),
+            GameState::Playing | GameState::Dying => None,
+            GameState::Paused => Some("pause_element"),
+            GameState::Over => Some("game_over_element"),
+        }
+    }
+
+    pub fn shows_board(&self) -> bool {
+        matches!(
+            *self,
+            GameState::Playing | GameState::Dying | GameState::Paused | GameState::Over
+        )
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PlayerDirection {
     Up,
     Down,
     Left,
     Right,
 }
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum GameMode {
     Classic,
     WallPass,
 }
 
-impl Display for GameMode {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl GameMode {
+    // Stable name used in save files
+    pub fn key(&self) -> &'static str {
         match *self {
-            GameMode::Classic => write!(f, "Classic"),
-            GameMode::WallPass => write!(f, "Wall Pass"),
+            GameMode::Classic => "classic",
+            GameMode::WallPass => "wall_pass",
+        }
+    }
+
+    // Key of the name shown in menus and the HUD, see assets/i18n
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            GameMode::Classic => "mode_classic",
+            GameMode::WallPass => "mode_wall_pass",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<GameMode> {
+        match key {
+            "classic" => Some(GameMode::Classic),
+            "wall_pass" => Some(GameMode::WallPass),
+            _ => None,
         }
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Point(pub i32, pub i32);
 
 impl Point {
@@ -70,12 +149,44 @@ impl Add<Point> for Point {
 
 pub struct GameContext {
     pub player_position: Vec<Point>,
+    // Where the snake was before the last tick, for drawing it in between
+    pub previous_player_position: Vec<Point>,
     pub player_direction: PlayerDirection,
     pub food: Point,
     pub state: GameState,
     pub mode: GameMode,
     pub score: i32,
+    // What the HUD shows while it counts up to `score`
+    pub displayed_score: i32,
+    pub actors: Vec<Actor>,
+    pub terrain: TerrainMap,
+    pub power_up: Option<Point>,
+    pub has_wall_breaker: bool,
     pub text_elements: HashMap<String, TextElement>,
+    pub menus: HashMap<String, Menu>,
+    pub settings: Settings,
+    pub themes: Vec<Theme>,
+    pub localization: Localization,
+    // The selected theme with the color preset applied
+    active_theme: Theme,
+    pub high_scores: HighScores,
+    // Where new high scores are saved, None keeps them in memory
+    high_scores_path: Option<PathBuf>,
+    pub new_high_score: bool,
+    pub stats: RunStats,
+    pub death: Option<DeathAnimation>,
+    pub quit_requested: bool,
+    events: Vec<GameEvent>,
+    // When the layout file was last read, to notice edits
+    layout_modified: Option<SystemTime>,
+    state_stack: Vec<GameState>,
+    frame_counter: u32,
+    popup_counter: u32,
+    transition_frames: u32,
+    countdown_frames: u32,
+    buffered_turns: VecDeque<PlayerDirection>,
+    turn_locked: bool,
+    stuck_in_mud: bool,
 }
 
 impl Default for GameContext {
@@ -86,49 +197,572 @@ impl Default for GameContext {
 
 impl GameContext {
     pub fn new() -> GameContext {
-        let text_elements = text_data::get_game_context_text();
-        let half_x = (GRID_X_SIZE / 2) as i32;
-        let half_y = (GRID_Y_SIZE / 2) as i32;
+        let path = assets::data_path(HIGH_SCORES_PATH);
+        let high_scores = HighScores::load(&path).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            HighScores::new()
+        });
+        GameContext::with_high_scores(high_scores, Some(path))
+    }
 
-        GameContext {
-            player_position: vec![
-                Point(half_x, half_y),
-                Point(half_x - 1, half_y),
-                Point(half_x - 2, half_y),
-            ],
+    pub fn with_high_scores(
+        high_scores: HighScores,
+        high_scores_path: Option<PathBuf>,
+    ) -> GameContext {
+        let mut context = GameContext {
+            player_position: Vec::new(),
+            previous_player_position: Vec::new(),
             player_direction: PlayerDirection::Right,
-            state: GameState::Paused,
+            state: GameState::Title,
             mode: GameMode::Classic,
-            food: Point::new_no_intersect(&vec![
-                Point(half_x, half_y),
-                Point(half_x - 1, half_y),
-                Point(half_x - 2, half_y),
-            ]),
+            food: Point(0, 0),
             score: 0,
-            text_elements,
+            displayed_score: 0,
+            actors: Vec::new(),
+            terrain: TerrainMap::new(),
+            power_up: None,
+            has_wall_breaker: false,
+            text_elements: text_data::get_game_context_text(),
+            menus: text_data::get_menus(),
+            settings: Settings::new(),
+            themes: Theme::load_all(THEMES_PATH),
+            localization: Localization::load_all(I18N_PATH),
+            active_theme: Theme::new(),
+            high_scores,
+            high_scores_path,
+            new_high_score: false,
+            stats: RunStats::new(),
+            death: None,
+            quit_requested: false,
+            events: Vec::new(),
+            layout_modified: layout_modified_time(),
+            state_stack: Vec::new(),
+            frame_counter: 0,
+            popup_counter: 0,
+            transition_frames: 0,
+            countdown_frames: 0,
+            buffered_turns: VecDeque::new(),
+            turn_locked: false,
+            stuck_in_mud: false,
+        };
+        context.reset_board();
+        context.refresh_theme();
+        context.refresh_language();
+        context.refresh_screen();
+        context
+    }
+
+    // Put a fresh board in place, keeping settings, scores and the screen stack
+    pub fn reset_board(&mut self) {
+        let half_x = (GRID_X_SIZE / 2) as i32;
+        let half_y = (GRID_Y_SIZE / 2) as i32;
+        self.player_position = vec![
+            Point(half_x, half_y),
+            Point(half_x - 1, half_y),
+            Point(half_x - 2, half_y),
+        ];
+        self.previous_player_position = self.player_position.clone();
+        self.player_direction = PlayerDirection::Right;
+        self.terrain = terrain_data::get_default_terrain();
+        self.power_up = None;
+        self.food = Point(-1, -1);
+        self.actors.clear();
+        self.actors = actor_data::get_initial_actors(&self.blocked_points());
+        self.food = Point::new_no_intersect(&self.blocked_points());
+        self.power_up = Some(Point::new_no_intersect(&self.blocked_points()));
+        self.has_wall_breaker = false;
+        self.turn_locked = false;
+        self.stuck_in_mud = false;
+        self.buffered_turns.clear();
+        self.score = 0;
+        self.displayed_score = 0;
+        self.remove_popups();
+        self.new_high_score = false;
+        self.stats = RunStats::new();
+        self.death = None;
+    }
+
+    // Advance one frame, the board only moves every few frames depending on the speed setting
+    pub fn update(&mut self) {
+        let first_event = self.events.len();
+        if self.transition_frames > 0 {
+            self.transition_frames -= 1;
+        }
+
+        if self.countdown_frames > 0 && self.state == GameState::Playing {
+            self.countdown_frames -= 1;
+            self.update_countdown_visibility();
+        }
+
+        if let Some(death) = &mut self.death {
+            death.update();
+            if death.is_finished() && self.state == GameState::Dying {
+                self.update_display_summary();
+                self.replace_state(GameState::Over);
+            }
+        }
+
+        self.update_text_animations();
+        if self.frame_counter.is_multiple_of(FRAMES_PER_SECOND) {
+            self.reload_layout_if_changed();
+        }
+
+        self.frame_counter += 1;
+        if self
+            .frame_counter
+            .is_multiple_of(self.settings.speed.tick_frames())
+        {
+            self.do_next_tick();
+        }
+        self.record_stats(first_event);
+    }
+
+    // The run's stats follow the events, like any other subscriber but without a frontend
+    fn record_stats(&mut self, first_event: usize) {
+        let mut stats = std::mem::take(&mut self.stats);
+        stats.handle_events(&self.events[first_event..], self);
+        self.stats = stats;
+    }
+
+    // Values for the text templates, taken fresh every frame
+    pub fn variables(&self) -> GameVariables {
+        GameVariables {
+            score: self.displayed_score,
+            mode: self.localization.get(self.mode.message_key()).to_string(),
+            countdown: self.countdown_frames.div_ceil(FRAMES_PER_SECOND),
+            length: self.player_position.len(),
+            speed: self
+                .localization
+                .get(self.settings.speed.message_key())
+                .to_string(),
+            best: self
+                .high_scores
+                .entries
+                .first()
+                .map_or(0, |entry| entry.score),
+            food_eaten: self.stats.food_eaten,
+            time_alive: self.stats.time_alive(),
+        }
+    }
+
+    pub fn is_counting_down(&self) -> bool {
+        self.countdown_frames > 0
+    }
+
+    // Everything that happened since the last call, frontends take these once per frame
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // How far into the current tick the game is, from just after the last tick up to 1.0
+    // when the next one is due. Stays at 1.0 whenever the snake isn't moving.
+    pub fn tick_progress(&self) -> f32 {
+        if self.state != GameState::Playing || self.countdown_frames > 0 {
+            return 1.0;
+        }
+        let tick_frames = self.settings.speed.tick_frames();
+        (self.frame_counter % tick_frames + 1) as f32 / tick_frames as f32
+    }
+
+    // Opacity of the fade shown right after switching screens
+    pub fn transition_alpha(&self) -> u8 {
+        (self.transition_frames * 200 / TRANSITION_FRAMES) as u8
+    }
+
+    pub fn handle_input(&mut self, action: InputAction) {
+        match self.state {
+            GameState::Title => {
+                if let InputAction::Confirm = action {
+                    self.events.push(GameEvent::MenuSelected);
+                    self.replace_state(GameState::MainMenu);
+                }
+            }
+            GameState::Playing => match action {
+                InputAction::Up => self.move_player(PlayerDirection::Up),
+                InputAction::Down => self.move_player(PlayerDirection::Down),
+                InputAction::Left => self.move_player(PlayerDirection::Left),
+                InputAction::Right => self.move_player(PlayerDirection::Right),
+                InputAction::Confirm | InputAction::Back => self.toggle_pause(),
+            },
+            // The animation runs to the end so the Game Over screen and high scores aren't skipped
+            GameState::Dying => {}
+            _ => self.handle_menu_input(action),
+        }
+    }
+
+    fn handle_menu_input(&mut self, action: InputAction) {
+        if let InputAction::Back = action {
+            self.events.push(GameEvent::MenuBack);
+            match self.state {
+                GameState::MainMenu => self.replace_state(GameState::Title),
+                GameState::Over => self.do_menu_action(MenuAction::OpenMainMenu),
+                GameState::Paused => self.toggle_pause(),
+                _ => self.pop_state(),
+            }
+            return;
+        }
+
+        let menu = match self
+            .state
+            .element_key()
+            .and_then(|key| self.menus.get_mut(key))
+        {
+            Some(menu) => menu,
+            None => return,
+        };
+
+        let chosen_action = match action {
+            InputAction::Up => {
+                menu.select_previous();
+                self.events.push(GameEvent::MenuMoved);
+                None
+            }
+            InputAction::Down => {
+                menu.select_next();
+                self.events.push(GameEvent::MenuMoved);
+                None
+            }
+            // Left and right only change values in place, never open screens
+            InputAction::Left => menu
+                .selected_action()
+                .filter(|selected| selected.is_setting())
+                .map(|selected| selected.reversed()),
+            InputAction::Right => menu
+                .selected_action()
+                .filter(|selected| selected.is_setting()),
+            InputAction::Confirm => menu.selected_action(),
+            InputAction::Back => None,
+        };
+
+        if let Some(menu_action) = chosen_action {
+            self.events.push(if menu_action == MenuAction::Back {
+                GameEvent::MenuBack
+            } else {
+                GameEvent::MenuSelected
+            });
+            self.do_menu_action(menu_action);
+        }
+        self.refresh_menu();
+    }
+
+    fn do_menu_action(&mut self, action: MenuAction) {
+        match action {
+            MenuAction::OpenMainMenu => {
+                self.state_stack.clear();
+                self.replace_state(GameState::MainMenu);
+            }
+            MenuAction::OpenModeSelect => self.push_state(GameState::ModeSelect),
+            MenuAction::OpenOptions => self.push_state(GameState::Options),
+            MenuAction::OpenHighScores => {
+                self.update_display_high_scores();
+                self.push_state(GameState::HighScores);
+            }
+            MenuAction::StartGame(mode) => {
+                if mode != self.mode {
+                    self.mode = mode;
+                    self.events.push(GameEvent::ModeChanged { mode });
+                }
+                self.reset_board();
+                self.state_stack = vec![GameState::MainMenu];
+                self.replace_state(GameState::Playing);
+                self.start_countdown();
+            }
+            MenuAction::PlayAgain => {
+                self.reset_board();
+                self.replace_state(GameState::Playing);
+                self.start_countdown();
+            }
+            MenuAction::Resume => self.toggle_pause(),
+            MenuAction::Back => self.pop_state(),
+            MenuAction::CycleSpeed => {
+                self.settings.speed = self.settings.speed.next();
+                self.update_option_labels();
+            }
+            MenuAction::CycleTheme => {
+                self.settings.theme = (self.settings.theme + 1) % self.themes.len();
+                self.refresh_theme();
+                self.update_option_labels();
+            }
+            MenuAction::CycleColorPreset => {
+                self.settings.color_preset = self.settings.color_preset.next();
+                self.refresh_theme();
+                self.update_option_labels();
+            }
+            MenuAction::ToggleShapeMarkers => {
+                self.settings.shape_markers = !self.settings.shape_markers;
+                self.update_option_labels();
+            }
+            MenuAction::ToggleReducedMotion => {
+                self.settings.reduced_motion = !self.settings.reduced_motion;
+                self.update_option_labels();
+            }
+            MenuAction::ToggleSmoothMovement => {
+                self.settings.smooth_movement = !self.settings.smooth_movement;
+                self.update_option_labels();
+            }
+            MenuAction::CycleEffects => {
+                self.settings.effects = self.settings.effects.next();
+                self.update_option_labels();
+            }
+            MenuAction::CycleLanguage => {
+                self.settings.language =
+                    (self.settings.language + 1) % self.localization.catalogs.len();
+                self.refresh_language();
+            }
+            MenuAction::ChangeVolume(channel, step) => {
+                let volume = match channel {
+                    VolumeChannel::Music => &mut self.settings.music_volume,
+                    VolumeChannel::Sound => &mut self.settings.sound_volume,
+                };
+                *volume = (*volume as i32 + step as i32).rem_euclid(VOLUME_STEPS as i32 + 1) as u8;
+                self.update_option_labels();
+            }
+            MenuAction::ToggleMute => {
+                self.settings.muted = !self.settings.muted;
+                self.update_option_labels();
+            }
+            MenuAction::Quit => self.quit_requested = true,
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.active_theme
+    }
+
+    fn refresh_theme(&mut self) {
+        self.active_theme = self.themes[self.settings.theme]
+            .clone()
+            .with_preset(self.settings.color_preset);
+    }
+
+    // Put every text the game shows into the selected language
+    fn refresh_language(&mut self) {
+        self.localization.select(self.settings.language);
+        self.localize_lines();
+        for menu in self.menus.values_mut() {
+            menu.localize(&self.localization);
+        }
+        self.update_option_labels();
+        for (key, menu) in &self.menus {
+            if let Some(element) = self.text_elements.get_mut(key) {
+                menu.apply_to(element);
+            }
+        }
+        self.update_display_high_scores();
+        if self.state == GameState::Over {
+            self.update_display_summary();
+        }
+    }
+
+    // Lines from the layout file that name a message take their text from the catalog
+    fn localize_lines(&mut self) {
+        for element in self.text_elements.values_mut() {
+            for line in element.lines.values_mut() {
+                if let Some(message) = &line.message {
+                    line.text = self.localization.get(message).to_string();
+                }
+            }
+        }
+    }
+
+    fn update_option_labels(&mut self) {
+        let settings = &self.settings;
+        let movement = if settings.smooth_movement {
+            "movement_smooth"
+        } else {
+            "movement_grid"
+        };
+        let values = [
+            (MenuAction::CycleSpeed, settings.speed.message_key()),
+            (
+                MenuAction::CycleColorPreset,
+                settings.color_preset.message_key(),
+            ),
+            (
+                MenuAction::ToggleShapeMarkers,
+                on_off(settings.shape_markers),
+            ),
+            (
+                MenuAction::ToggleReducedMotion,
+                on_off(settings.reduced_motion),
+            ),
+            (MenuAction::ToggleSmoothMovement, movement),
+            (MenuAction::CycleEffects, settings.effects.message_key()),
+            (MenuAction::ToggleMute, on_off(settings.muted)),
+        ];
+        let mut labels: Vec<(MenuAction, &str)> = values
+            .into_iter()
+            .map(|(action, key)| (action, self.localization.get(key)))
+            .collect();
+        // Theme and language names come from their own files
+        labels.push((MenuAction::CycleTheme, &self.themes[settings.theme].name));
+        labels.push((
+            MenuAction::CycleLanguage,
+            &self.localization.language().name,
+        ));
+        let music_volume = volume_percent(settings.music_volume);
+        let sound_volume = volume_percent(settings.sound_volume);
+        labels.push((
+            MenuAction::ChangeVolume(VolumeChannel::Music, 1),
+            &music_volume,
+        ));
+        labels.push((
+            MenuAction::ChangeVolume(VolumeChannel::Sound, 1),
+            &sound_volume,
+        ));
+
+        let Some(menu) = self.menus.get_mut("options_element") else {
+            return;
+        };
+        for item in &mut menu.items {
+            if let Some((_, value)) = labels.iter().find(|(action, _)| *action == item.action) {
+                item.label = self.localization.format(&item.message, &[("value", value)]);
+            }
+        }
+    }
+
+    fn push_state(&mut self, state: GameState) {
+        self.state_stack.push(self.state);
+        self.state = state;
+        self.refresh_screen();
+    }
+
+    fn pop_state(&mut self) {
+        if let Some(state) = self.state_stack.pop() {
+            self.state = state;
+            self.refresh_screen();
+        }
+    }
+
+    fn replace_state(&mut self, state: GameState) {
+        self.state = state;
+        self.refresh_screen();
+    }
+
+    // Show only the text belonging to the current screen and start the fade
+    fn refresh_screen(&mut self) {
+        self.events
+            .push(GameEvent::ScreenChanged { state: self.state });
+        let current_key = self.state.element_key();
+        for state in [
+            GameState::Title,
+            GameState::MainMenu,
+            GameState::ModeSelect,
+            GameState::Options,
+            GameState::HighScores,
+            GameState::Paused,
+            GameState::Over,
+        ] {
+            let Some(key) = state.element_key() else {
+                continue;
+            };
+            if let Some(element) = self.text_elements.get_mut(key) {
+                element.visible = current_key == Some(key);
+            }
+        }
+
+        let shows_board = self.state.shows_board();
+        if !shows_board {
+            self.remove_popups();
+        }
+        for key in ["score_element", "mode_element"] {
+            if let Some(element) = self.text_elements.get_mut(key) {
+                element.visible = shows_board;
+            }
+        }
+        if let Some(element) = self.text_elements.get_mut("countdown_element") {
+            element.visible = self.state == GameState::Playing && self.countdown_frames > 0;
+        }
+
+        // The death sequence has its own effects, so don't fade into it
+        if self.state != GameState::Dying && !self.settings.reduced_motion {
+            self.transition_frames = TRANSITION_FRAMES;
+        }
+        self.refresh_menu();
+    }
+
+    fn refresh_menu(&mut self) {
+        if let Some(key) = self.state.element_key() {
+            if let (Some(menu), Some(element)) =
+                (self.menus.get(key), self.text_elements.get_mut(key))
+            {
+                menu.apply_to(element);
+            }
         }
     }
 
     pub fn do_next_tick(&mut self) {
-        if let GameState::Over = self.state {
-            // Death animation
-            self.player_position.pop();
-        } else if let GameState::Playing = self.state {
+        if let GameState::Playing = self.state {
+            if self.countdown_frames > 0 {
+                return;
+            }
+            self.previous_player_position = self.player_position.clone();
+            // Turns from the countdown are made one per tick, like pressed then, so ice still
+            // locks them and reversing into the neck is ignored
+            if let Some(direction) = self.buffered_turns.pop_front() {
+                let step = direction_step(direction) + direction_step(self.player_direction);
+                if !self.turn_locked && step != Point(0, 0) {
+                    self.turn(direction);
+                }
+            }
+
             let mut player_is_alive = true;
-            let current_player_head_pos = self.player_position.first().unwrap();
-            let mut next_player_head_pos = match self.player_direction {
-                PlayerDirection::Up => *current_player_head_pos + Point(0, -1),
-                PlayerDirection::Down => *current_player_head_pos + Point(0, 1),
-                PlayerDirection::Left => *current_player_head_pos + Point(-1, 0),
-                PlayerDirection::Right => *current_player_head_pos + Point(1, 0),
+            let Some(&current_player_head_pos) = self.player_position.first() else {
+                return;
             };
+            let current_tile = self.terrain.get(current_player_head_pos);
+
+            // Mud only lets the snake move every other tick
+            if let TerrainTile::Mud = current_tile {
+                self.stuck_in_mud = !self.stuck_in_mud;
+                if self.stuck_in_mud {
+                    self.update_actors();
+                    return;
+                }
+            } else {
+                self.stuck_in_mud = false;
+            }
+
+            // Direction the head actually moves in, gates check this rather than the heading
+            let mut moved = self.player_direction;
+            let mut step = direction_step(moved);
+
+            // Conveyors carry a snake crossing them sideways instead of letting it move on, as
+            // long as that stays on the board. Along or against the belt it moves normally, so
+            // the snake always enters exactly one cell per tick.
+            if let TerrainTile::Conveyor(push) = current_tile {
+                let push_step = direction_step(push);
+                let pushed = current_player_head_pos + push_step;
+                if step.0 * push_step.0 + step.1 * push_step.1 == 0
+                    && pushed.0 >= 0
+                    && pushed.0 < GRID_X_SIZE as i32
+                    && pushed.1 >= 0
+                    && pushed.1 < GRID_Y_SIZE as i32
+                {
+                    moved = push;
+                    step = push_step;
+                }
+            }
+            let mut next_player_head_pos = current_player_head_pos + step;
 
             // Detect snake collision with food
             if next_player_head_pos == self.food {
                 self.player_position.push(Point(0, 0));
-                self.score += 1;
-                self.update_display_score();
-                self.food = Point::new_no_intersect(&self.player_position);
+                self.add_score(1, self.food);
+                self.events.push(GameEvent::FoodEaten { at: self.food });
+                self.events.push(GameEvent::Grew {
+                    length: self.player_position.len(),
+                });
+                self.food = Point::new_no_intersect(&self.blocked_points());
+            }
+
+            // Detect snake collision with power-up
+            if self.power_up == Some(next_player_head_pos) {
+                self.power_up = None;
+                self.has_wall_breaker = true;
+                self.events.push(GameEvent::PowerUpCollected {
+                    at: next_player_head_pos,
+                });
             }
 
             // Detect snake collision with snake
@@ -140,12 +774,13 @@ impl GameContext {
             }
 
             if !player_is_alive {
-                self.game_over();
+                self.game_over(DeathCause::SelfCollision);
             }
 
             // Detect snake collision with walls
             let signed_grid_x_size = GRID_X_SIZE as i32;
             let signed_grid_y_size = GRID_Y_SIZE as i32;
+            let unwrapped_head_pos = next_player_head_pos;
 
             match self.mode {
                 GameMode::Classic => {
@@ -154,7 +789,7 @@ impl GameContext {
                         || next_player_head_pos.1 == -1
                         || next_player_head_pos.1 == signed_grid_y_size + 1
                     {
-                        self.game_over();
+                        self.game_over(DeathCause::Wall);
                     }
                 }
                 GameMode::WallPass => match self.player_direction {
@@ -183,66 +818,664 @@ impl GameContext {
                 },
             }
 
+            if next_player_head_pos != unwrapped_head_pos {
+                self.events.push(GameEvent::Wrapped {
+                    exit: unwrapped_head_pos,
+                    entry: next_player_head_pos,
+                });
+            }
+
+            // Detect snake collision with gates and breakable walls
+            match self.terrain.get(next_player_head_pos) {
+                TerrainTile::OneWayGate(allowed) if allowed != moved => {
+                    self.game_over(DeathCause::OneWayGate);
+                }
+                TerrainTile::BreakableWall => {
+                    if self.has_wall_breaker {
+                        self.has_wall_breaker = false;
+                        self.terrain.set(next_player_head_pos, TerrainTile::Floor);
+                        self.power_up = Some(Point::new_no_intersect(&self.blocked_points()));
+                    } else {
+                        self.game_over(DeathCause::BreakableWall);
+                    }
+                }
+                _ => {}
+            }
+
             self.player_position.pop();
             self.player_position.reverse();
             self.player_position.push(next_player_head_pos);
             self.player_position.reverse();
+
+            // Ice keeps the snake sliding straight for one tick
+            self.turn_locked = matches!(self.terrain.get(next_player_head_pos), TerrainTile::Ice);
+
+            self.update_actors();
         }
     }
 
+    // Collisions are checked before the actors move as well as after, so the head landing on
+    // an actor counts even when that actor would have moved away this tick
+    fn update_actors(&mut self) {
+        self.check_actor_collisions();
+        if self.state != GameState::Playing {
+            return;
+        }
+        for actor in &mut self.actors {
+            actor.update(&self.player_position, &self.terrain);
+        }
+        self.check_actor_collisions();
+    }
+
+    // Hazards kill when they share a cell with any part of the snake, the mouse is only
+    // caught by the head
+    fn check_actor_collisions(&mut self) {
+        let Some(&head) = self.player_position.first() else {
+            return;
+        };
+        let mut bonus = 0;
+        let mut hit_hazard: Option<ActorKind> = None;
+        let mut caught = Vec::new();
+        for (i, actor) in self.actors.iter().enumerate() {
+            if !actor.alive {
+                continue;
+            }
+            if actor.is_hazard() {
+                if self.player_position.contains(&actor.position) {
+                    hit_hazard = Some(actor.kind);
+                }
+            } else if actor.position == head {
+                bonus += actor.bonus;
+                caught.push(i);
+            }
+        }
+        for i in caught {
+            self.actors[i].position = Point::new_no_intersect(&self.blocked_points());
+        }
+
+        if bonus > 0 {
+            self.add_score(bonus, head);
+        }
+        if let Some(kind) = hit_hazard {
+            self.game_over(DeathCause::Hazard(kind));
+        }
+    }
+
+    // Turns made during the countdown are queued and played back one per tick
     pub fn move_player(&mut self, direction: PlayerDirection) {
-        self.player_direction = direction;
+        if self.countdown_frames > 0 {
+            if self.buffered_turns.len() < MAX_BUFFERED_TURNS {
+                self.buffered_turns.push_back(direction);
+            }
+        } else if !self.turn_locked {
+            self.turn(direction);
+        }
+    }
+
+    fn turn(&mut self, direction: PlayerDirection) {
+        if direction != self.player_direction {
+            self.player_direction = direction;
+            self.events.push(GameEvent::Turned { direction });
+        }
+    }
+
+    fn start_countdown(&mut self) {
+        self.countdown_frames = COUNTDOWN_FRAMES;
+        self.previous_player_position = self.player_position.clone();
+        self.update_countdown_visibility();
     }
 
     pub fn toggle_pause(&mut self) {
-        self.state = match self.state {
-            GameState::Playing => GameState::Paused,
-            GameState::Paused => GameState::Playing,
-            GameState::Over => GameState::Over,
-        };
-        let pause_element = self.text_elements.get_mut("pause_element").unwrap();
-        if let GameState::Paused = self.state {
-            pause_element.visible = true;
+        match self.state {
+            GameState::Playing => {
+                if let Some(menu) = self.menus.get_mut("pause_element") {
+                    menu.selected = 0;
+                }
+                self.events.push(GameEvent::Paused);
+                self.push_state(GameState::Paused);
+            }
+            GameState::Paused => {
+                self.events.push(GameEvent::Resumed);
+                self.pop_state();
+                self.start_countdown();
+            }
+            _ => {}
+        }
+    }
+
+    // Cells new pickups must not spawn on
+    fn blocked_points(&self) -> Vec<Point> {
+        let mut blocked = self.player_position.clone();
+        blocked.extend(self.terrain.tiles().map(|(p, _)| p));
+        blocked.extend(self.actors.iter().map(|actor| actor.position));
+        blocked.push(self.food);
+        if let Some(power_up) = self.power_up {
+            blocked.push(power_up);
+        }
+        blocked
+    }
+
+    // Only the first collision of a tick counts as the cause of death
+    fn game_over(&mut self, cause: DeathCause) {
+        if self.state != GameState::Playing {
+            return;
+        }
+        self.events.push(GameEvent::Died {
+            cause,
+            segments: self.player_position.clone(),
+        });
+        let sequence = if self.settings.reduced_motion {
+            self.settings.death_sequence.without_motion()
         } else {
-            pause_element.visible = false;
+            self.settings.death_sequence
+        };
+        self.death = Some(DeathAnimation::new(&self.player_position, sequence));
+        self.replace_state(GameState::Dying);
+
+        self.new_high_score = self.high_scores.add(self.score, self.mode);
+        if self.new_high_score {
+            self.events.push(GameEvent::HighScore {
+                score: self.score,
+                mode: self.mode,
+            });
+            if let Some(path) = &self.high_scores_path {
+                if let Err(e) = self.high_scores.save(path) {
+                    eprintln!("{e}");
+                }
+            }
         }
     }
 
-    pub fn toggle_mode(&mut self) {
-        self.mode = match self.mode {
-            GameMode::WallPass => GameMode::Classic,
-            GameMode::Classic => GameMode::WallPass,
+    // Pops a "+N" where the points were scored and pulses the score in the HUD
+    fn add_score(&mut self, points: i32, at: Point) {
+        self.score += points;
+
+        let size = DOT_SIZE_IN_PXS as i32;
+        let mut popup = TextElement::new(
+            3,
+            Position2D::new(at.0 * size, at.1 * size - size / 2),
+            Anchor::TopLeft,
+            false,
+        );
+        popup.lines.insert(
+            "popup_line".to_string(),
+            TextLine::new(
+                0,
+                Position2D::new(0, 0),
+                TextAlignment::Start,
+                FontDefinition::new(FontName::ArcadeNormal, 16, Color::YELLOW)
+                    .with_style(TextStyle::Highlight),
+                format!("+{points}"),
+            ),
+        );
+        popup.animation = TextAnimation {
+            offset_y: Some(Tween::new(0.0, -30.0, 45, Easing::EaseOut)),
+            alpha: Some(Tween::new(255.0, 0.0, 45, Easing::Linear)),
+            remove_when_finished: true,
+            ..Default::default()
         };
+        self.popup_counter += 1;
+        self.text_elements
+            .insert(format!("popup_{}", self.popup_counter), popup);
 
-        self.score = 0;
-        let new_player_position = vec![
-            self.player_position[0],
-            self.player_position[1],
-            self.player_position[2],
+        if let Some(score_element) = self.text_elements.get_mut("score_element") {
+            score_element.animation.scale = Some(Tween::new(1.0, 1.4, 16, Easing::PingPong));
+        }
+    }
+
+    fn update_text_animations(&mut self) {
+        // The HUD counts up a step every other frame, bigger steps for bigger gaps
+        if self.displayed_score != self.score && self.frame_counter.is_multiple_of(2) {
+            let gap = self.score - self.displayed_score;
+            self.displayed_score += (gap.abs() / 8).max(1) * gap.signum();
+        }
+
+        for element in self.text_elements.values_mut() {
+            element.animation.update();
+        }
+        self.text_elements.retain(|_, element| {
+            !(element.animation.remove_when_finished && element.animation.is_finished())
+        });
+    }
+
+    fn reload_layout_if_changed(&mut self) {
+        let modified = layout_modified_time();
+        if modified.is_none() || modified == self.layout_modified {
+            return;
+        }
+        self.layout_modified = modified;
+        match ui_layout::load(UI_LAYOUT_PATH) {
+            Ok(elements) => self.apply_layout(elements),
+            Err(e) => eprintln!("Keeping the current layout: {e}"),
+        }
+    }
+
+    // Swap in freshly loaded elements, keeping what the game has changed since startup
+    fn apply_layout(&mut self, mut elements: HashMap<String, TextElement>) {
+        for (key, old) in self.text_elements.drain() {
+            match elements.get_mut(&key) {
+                Some(element) => {
+                    element.visible = old.visible;
+                    element.animation = old.animation;
+                }
+                None if old.animation.remove_when_finished => {
+                    elements.insert(key, old);
+                }
+                None => {}
+            }
+        }
+        self.text_elements = elements;
+        self.localize_lines();
+
+        // Put back the lines the game writes itself
+        for (key, menu) in &self.menus {
+            if let Some(element) = self.text_elements.get_mut(key) {
+                menu.apply_to(element);
+            }
+        }
+        self.update_countdown_visibility();
+        self.update_display_high_scores();
+        if self.state == GameState::Over {
+            self.update_display_summary();
+        }
+    }
+
+    fn remove_popups(&mut self) {
+        self.text_elements
+            .retain(|_, element| !element.animation.remove_when_finished);
+    }
+
+    fn update_display_summary(&mut self) {
+        let strings = &self.localization;
+        let summary_line =
+            |key: &str, value: String| (strings.format(key, &[("value", &value)]), TextStyle::Hud);
+        let mut summary = vec![
+            summary_line("summary_score", self.score.to_string()),
+            summary_line("summary_length", self.player_position.len().to_string()),
+            summary_line("summary_time_alive", self.stats.time_alive()),
+            summary_line("summary_food_eaten", self.stats.food_eaten.to_string()),
         ];
+        if let Some(cause) = self.stats.cause_of_death {
+            summary.push((strings.get(cause.message_key()).to_string(), TextStyle::Hud));
+        }
+        if self.new_high_score {
+            summary.push((
+                strings.get("new_high_score").to_string(),
+                TextStyle::Highlight,
+            ));
+        }
 
-        self.player_position = new_player_position;
+        let Some(ui_game_over) = self.text_elements.get_mut("game_over_element") else {
+            return;
+        };
+        ui_game_over
+            .lines
+            .retain(|key, _| !key.starts_with("summary_line_"));
+        for (i, (text, style)) in summary.into_iter().enumerate() {
+            let line = TextLine::new(
+                i as u32 + 1,
+                Position2D::new(0, 0),
+                TextAlignment::Center,
+                FontDefinition::new(FontName::ArcadeNormal, 16, Color::WHITE).with_style(style),
+                text,
+            );
+            ui_game_over.lines.insert(format!("summary_line_{i}"), line);
+        }
+    }
 
-        self.update_display_score();
-        self.update_display_game_mode();
+    fn update_countdown_visibility(&mut self) {
+        if let Some(element) = self.text_elements.get_mut("countdown_element") {
+            element.visible = self.countdown_frames > 0;
+        }
     }
 
-    fn game_over(&mut self) {
-        self.state = GameState::Over;
-        let game_over_text_element = self.text_elements.get_mut("game_over_element").unwrap();
-        game_over_text_element.visible = true;
-        println!("Final score: {}!", self.score);
+    fn update_display_high_scores(&mut self) {
+        let Some(ui_high_scores) = self.text_elements.get_mut("high_scores_element") else {
+            return;
+        };
+        ui_high_scores
+            .lines
+            .retain(|key, _| !key.starts_with("high_score_entry_"));
+
+        let mut entries: Vec<String> = self
+            .high_scores
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                self.localization.format(
+                    "high_score_entry",
+                    &[
+                        ("rank", &(i + 1).to_string()),
+                        ("score", &entry.score.to_string()),
+                        ("mode", self.localization.get(entry.mode.message_key())),
+                    ],
+                )
+            })
+            .collect();
+        if entries.is_empty() {
+            entries.push(self.localization.get("no_scores").to_string());
+        }
+
+        for (i, entry) in entries.into_iter().enumerate() {
+            let line = TextLine::new(
+                i as u32 + 1,
+                Position2D::new(0, 0),
+                TextAlignment::Center,
+                FontDefinition::new(FontName::ArcadeNormal, 16, Color::WHITE)
+                    .with_style(TextStyle::Hud),
+                entry,
+            );
+            ui_high_scores
+                .lines
+                .insert(format!("high_score_entry_{i}"), line);
+        }
     }
+}
+
+fn layout_modified_time() -> Option<SystemTime> {
+    fs::metadata(assets::path(UI_LAYOUT_PATH))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn volume_percent(volume: u8) -> String {
+    format!("{}%", volume as u32 * 100 / VOLUME_STEPS as u32)
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
 
-    fn update_display_score(&mut self) {
-        let ui_score_text = self.text_elements.get_mut("score_element").unwrap();
-        let ui_score_text = ui_score_text.lines.get_mut("score_line").unwrap();
-        ui_score_text.text = self.score.to_string();
+fn direction_step(direction: PlayerDirection) -> Point {
+    match direction {
+        PlayerDirection::Up => Point(0, -1),
+        PlayerDirection::Down => Point(0, 1),
+        PlayerDirection::Left => Point(-1, 0),
+        PlayerDirection::Right => Point(1, 0),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::actors::is_open;
+    use crate::entities::text_template::TextTemplate;
+
+    // High scores stay in memory so tests never touch the player's saved ones
+    fn test_context() -> GameContext {
+        GameContext::with_high_scores(HighScores::new(), None)
+    }
+
+    // A snake of three heading right from (10, 10) on an empty board, already moving
+    fn playing_context() -> GameContext {
+        let mut context = test_context();
+        context.state = GameState::Playing;
+        context.countdown_frames = 0;
+        context.terrain = TerrainMap::new();
+        context.actors.clear();
+        context.food = Point(0, 0);
+        context.power_up = None;
+        context.player_position = vec![Point(10, 10), Point(9, 10), Point(8, 10)];
+        context.player_direction = PlayerDirection::Right;
+        context
+    }
+
+    // One board step, with the stats following its events like during update()
+    fn tick(context: &mut GameContext) {
+        let first_event = context.events.len();
+        context.do_next_tick();
+        context.record_stats(first_event);
+    }
+
+    #[test]
+    fn ball_moving_onto_the_head_kills() {
+        let mut context = playing_context();
+        context.actors = vec![Actor::new_bouncing_ball(Point(12, 9), Point(-1, 1))];
+        tick(&mut context);
+        assert_eq!(context.player_position[0], Point(11, 10));
+        assert_eq!(context.state, GameState::Dying);
+        assert_eq!(
+            context.stats.cause_of_death,
+            Some(DeathCause::Hazard(ActorKind::BouncingBall))
+        );
+    }
+
+    #[test]
+    fn head_moving_onto_a_ball_kills() {
+        let mut context = playing_context();
+        context.actors = vec![Actor::new_bouncing_ball(Point(11, 10), Point(1, 1))];
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Dying);
+    }
+
+    #[test]
+    fn patrol_block_crushes_the_body() {
+        let mut context = playing_context();
+        // Moves every second tick, by then the body has moved on to (10, 10)
+        context.actors = vec![Actor::new_patrol_block(Point(10, 11), Point(0, -1), 5)];
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Playing);
+        tick(&mut context);
+        assert_eq!(context.player_position[2], Point(10, 10));
+        assert_eq!(context.state, GameState::Dying);
+        assert_eq!(
+            context.stats.cause_of_death,
+            Some(DeathCause::Hazard(ActorKind::PatrolBlock))
+        );
+    }
+
+    #[test]
+    fn patrol_block_stays_put_when_boxed_in() {
+        let mut terrain = TerrainMap::new();
+        terrain.set(Point(1, 5), TerrainTile::BreakableWall);
+        let mut block = Actor::new_patrol_block(Point(0, 5), Point(1, 0), 5);
+        for _ in 0..6 {
+            block.update(&[], &terrain);
+            assert_eq!(block.position, Point(0, 5));
+        }
+    }
+
+    #[test]
+    fn mouse_is_caught_on_its_move_tick() {
+        let mut context = playing_context();
+        context.actors = vec![Actor::new_mouse(Point(12, 10), actor_data::MOUSE_BONUS)];
+        tick(&mut context);
+        // The mouse would run on this tick, but the head gets there first
+        tick(&mut context);
+        assert_eq!(context.player_position[0], Point(12, 10));
+        assert_eq!(context.score, actor_data::MOUSE_BONUS);
+        assert_eq!(context.state, GameState::Playing);
+        assert_ne!(context.actors[0].position, Point(12, 10));
+    }
+
+    #[test]
+    fn actors_and_pickups_spawn_on_free_cells() {
+        let mut context = test_context();
+        for _ in 0..50 {
+            context.reset_board();
+            let pickups = [Some(context.food), context.power_up];
+            for actor in &context.actors {
+                let at = actor.position;
+                assert!(is_open(at, &context.terrain), "{:?} in a wall", actor.kind);
+                assert!(!context.player_position.contains(&at));
+                assert!(!pickups.contains(&Some(at)), "pickup on {:?}", actor.kind);
+                if !actor.is_hazard() {
+                    assert!(matches!(context.terrain.get(at), TerrainTile::Floor));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn back_does_not_skip_the_death_animation() {
+        let mut context = playing_context();
+        context.player_direction = PlayerDirection::Left;
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Dying);
+        context.handle_input(InputAction::Back);
+        assert_eq!(context.state, GameState::Dying);
+    }
+
+    #[test]
+    fn turns_from_the_countdown_are_made_one_per_tick() {
+        let mut context = playing_context();
+        context.countdown_frames = 1;
+        context.move_player(PlayerDirection::Up);
+        context.move_player(PlayerDirection::Left);
+        context.countdown_frames = 0;
+        tick(&mut context);
+        assert_eq!(context.player_position[0], Point(10, 9));
+        tick(&mut context);
+        assert_eq!(context.player_position[0], Point(9, 9));
+
+        // Once moving, turns are made right away
+        context.move_player(PlayerDirection::Down);
+        assert_eq!(context.player_direction, PlayerDirection::Down);
+    }
+
+    #[test]
+    fn reversing_during_the_countdown_is_ignored() {
+        let mut context = playing_context();
+        context.countdown_frames = 1;
+        context.move_player(PlayerDirection::Left);
+        context.countdown_frames = 0;
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Playing);
+        assert_eq!(context.player_position[0], Point(11, 10));
+    }
+
+    #[test]
+    fn turns_from_the_countdown_stay_locked_on_ice() {
+        let mut context = playing_context();
+        context.countdown_frames = 1;
+        context.move_player(PlayerDirection::Up);
+        context.countdown_frames = 0;
+        context.turn_locked = true;
+        tick(&mut context);
+        assert_eq!(context.player_direction, PlayerDirection::Right);
+    }
+
+    #[test]
+    fn snake_can_move_against_a_conveyor() {
+        let mut context = playing_context();
+        context.player_position = vec![Point(10, 10), Point(10, 11), Point(10, 12)];
+        context.player_direction = PlayerDirection::Up;
+        context
+            .terrain
+            .set(Point(10, 10), TerrainTile::Conveyor(PlayerDirection::Down));
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Playing);
+        assert_eq!(context.player_position[0], Point(10, 9));
+    }
+
+    #[test]
+    fn conveyor_along_the_heading_moves_one_cell() {
+        let mut context = playing_context();
+        context
+            .terrain
+            .set(Point(10, 10), TerrainTile::Conveyor(PlayerDirection::Right));
+        context.food = Point(11, 10);
+        tick(&mut context);
+        assert_eq!(
+            context.player_position[..3],
+            [Point(11, 10), Point(10, 10), Point(9, 10)]
+        );
+        assert_eq!(context.stats.food_eaten, 1);
+    }
+
+    #[test]
+    fn conveyor_across_the_heading_carries_the_snake_sideways() {
+        let mut context = playing_context();
+        context
+            .terrain
+            .set(Point(10, 10), TerrainTile::Conveyor(PlayerDirection::Down));
+        context.food = Point(10, 11);
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Playing);
+        assert_eq!(
+            context.player_position[..3],
+            [Point(10, 11), Point(10, 10), Point(9, 10)]
+        );
+        assert_eq!(context.stats.food_eaten, 1);
+    }
+
+    #[test]
+    fn gates_check_the_direction_a_conveyor_carries_the_snake() {
+        let mut context = playing_context();
+        context
+            .terrain
+            .set(Point(10, 10), TerrainTile::Conveyor(PlayerDirection::Down));
+        context.terrain.set(
+            Point(10, 11),
+            TerrainTile::OneWayGate(PlayerDirection::Down),
+        );
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Playing);
+        assert_eq!(context.player_position[0], Point(10, 11));
+
+        let mut context = playing_context();
+        context
+            .terrain
+            .set(Point(10, 10), TerrainTile::Conveyor(PlayerDirection::Down));
+        context.terrain.set(
+            Point(10, 11),
+            TerrainTile::OneWayGate(PlayerDirection::Right),
+        );
+        tick(&mut context);
+        assert_eq!(context.state, GameState::Dying);
+    }
+
+    #[test]
+    fn choosing_another_mode_is_announced() {
+        let mut context = test_context();
+        let mode = match context.mode {
+            GameMode::Classic => GameMode::WallPass,
+            GameMode::WallPass => GameMode::Classic,
+        };
+        context.do_menu_action(MenuAction::StartGame(mode));
+        let events = context.take_events();
+        assert!(events.contains(&GameEvent::ModeChanged { mode }));
+
+        context.do_menu_action(MenuAction::StartGame(mode));
+        let events = context.take_events();
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, GameEvent::ModeChanged { .. })));
+    }
+
+    #[test]
+    fn stats_count_frames_alive_only_after_the_countdown() {
+        let mut context = playing_context();
+        context.countdown_frames = 3;
+        while context.countdown_frames > 1 {
+            context.update();
+        }
+        assert_eq!(context.stats.frames_alive, 0);
+        for _ in 0..3 {
+            context.update();
+        }
+        assert_eq!(context.stats.frames_alive, 3);
+    }
+
+    #[test]
+    fn score_color_highlights_a_score_beating_the_saved_best() {
+        let mut high_scores = HighScores::new();
+        high_scores.add(10, GameMode::Classic);
+        let mut context = GameContext::with_high_scores(high_scores, None);
+        let render = |context: &GameContext, source| {
+            TextTemplate::parse(source)
+                .unwrap()
+                .render(&context.variables())
+        };
 
-    fn update_display_game_mode(&mut self) {
-        let ui_mode_text = self.text_elements.get_mut("mode_element").unwrap();
-        let ui_mode_text = ui_mode_text.lines.get_mut("game_mode_line").unwrap();
-        ui_mode_text.text = self.mode.to_string();
+        context.displayed_score = 10;
+        assert_eq!(render(&context, "{score_color} {best}"), "default 10");
+        context.displayed_score = 12;
+        assert_eq!(render(&context, "{score_color} {best}"), "highlight 12");
     }
 }
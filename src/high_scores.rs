@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::Path;
+
+use crate::{constants::MAX_HIGH_SCORES, error::GameError, game_context::GameMode};
+
+pub struct HighScoreEntry {
+    pub score: i32,
+    pub mode: GameMode,
+}
+
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HighScores {
+    pub fn new() -> HighScores {
+        HighScores {
+            entries: Vec::new(),
+        }
+    }
+
+    // A missing file just means nobody has played yet
+    pub fn load(path: &Path) -> Result<HighScores, GameError> {
+        let error = |message: String| GameError::SaveFile {
+            path: path.to_path_buf(),
+            message,
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HighScores::new()),
+            Err(e) => return Err(error(e.to_string())),
+        };
+
+        let mut high_scores = HighScores::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (score, mode) = line
+                .split_once(',')
+                .ok_or_else(|| error(format!("line {}: expected `score,mode`", i + 1)))?;
+            let score = score
+                .trim()
+                .parse::<i32>()
+                .map_err(|e| error(format!("line {}: {e}", i + 1)))?;
+            let mode = GameMode::from_key(mode.trim())
+                .ok_or_else(|| error(format!("line {}: unknown mode `{}`", i + 1, mode.trim())))?;
+            high_scores.entries.push(HighScoreEntry { score, mode });
+        }
+        high_scores.sort_and_truncate();
+        Ok(high_scores)
+    }
+
+    // Creates the folder on the first save
+    pub fn save(&self, path: &Path) -> Result<(), GameError> {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{},{}\n", entry.score, entry.mode.key()))
+            .collect();
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, contents))
+            .map_err(|e| GameError::SaveFile {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })
+    }
+
+    pub fn is_high_score(&self, score: i32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    // Returns whether the score made it onto the list
+    pub fn add(&mut self, score: i32, mode: GameMode) -> bool {
+        if !self.is_high_score(score) {
+            return false;
+        }
+        self.entries.push(HighScoreEntry { score, mode });
+        self.sort_and_truncate();
+        true
+    }
+
+    fn sort_and_truncate(&mut self) {
+        self.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.entries.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn saves_into_a_new_folder_and_loads_back() {
+        let dir = env::temp_dir().join(format!("snake-high-scores-{}", std::process::id()));
+        let path = dir.join("nested").join("highscores.txt");
+        assert!(HighScores::load(&path).unwrap().entries.is_empty());
+
+        let mut high_scores = HighScores::new();
+        assert!(high_scores.add(7, GameMode::Classic));
+        assert!(high_scores.add(12, GameMode::WallPass));
+        assert!(!high_scores.add(0, GameMode::Classic));
+        high_scores.save(&path).unwrap();
+
+        let loaded = HighScores::load(&path).unwrap();
+        let entries: Vec<(i32, GameMode)> = loaded
+            .entries
+            .iter()
+            .map(|entry| (entry.score, entry.mode))
+            .collect();
+        assert_eq!(entries, [(12, GameMode::WallPass), (7, GameMode::Classic)]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(feature = "sdl")]
+use sdl2::{controller::Button, keyboard::Keycode};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum InputAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+// Keys and buttons of the SDL frontend, the terminal one maps its own keys
+#[cfg(feature = "sdl")]
+impl InputAction {
+    pub fn from_keycode(keycode: Keycode) -> Option<InputAction> {
+        match keycode {
+            Keycode::A | Keycode::Left => Some(InputAction::Left),
+            Keycode::W | Keycode::Up => Some(InputAction::Up),
+            Keycode::D | Keycode::Right => Some(InputAction::Right),
+            Keycode::S | Keycode::Down => Some(InputAction::Down),
+            Keycode::Return | Keycode::KpEnter | Keycode::Space => Some(InputAction::Confirm),
+            Keycode::Escape | Keycode::Backspace => Some(InputAction::Back),
+            _ => None,
+        }
+    }
+
+    pub fn from_controller_button(button: Button) -> Option<InputAction> {
+        match button {
+            Button::DPadLeft => Some(InputAction::Left),
+            Button::DPadUp => Some(InputAction::Up),
+            Button::DPadRight => Some(InputAction::Right),
+            Button::DPadDown => Some(InputAction::Down),
+            Button::A => Some(InputAction::Confirm),
+            Button::B | Button::Start => Some(InputAction::Back),
+            _ => None,
+        }
+    }
+}
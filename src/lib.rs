@@ -1,6 +1,18 @@
+pub mod assets;
+pub mod audio;
+pub mod color;
 pub mod constants;
 pub mod data;
 pub mod data_structs;
 pub mod entities;
+pub mod error;
+pub mod events;
 pub mod game_context;
+pub mod high_scores;
+pub mod input;
+pub mod localization;
+#[cfg(feature = "sdl")]
 pub mod renderer;
+pub mod settings;
+pub mod stats;
+pub mod theme;
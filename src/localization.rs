@@ -0,0 +1,192 @@
+// Message catalogs for every language the game can be played in, see assets/i18n/en.lang
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::assets;
+use crate::data::kv_file::KvFile;
+use crate::error::GameError;
+
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+// Built in so the game still has text when the catalogs can't be read
+const DEFAULT_CATALOG: &str = include_str!("../assets/i18n/en.lang");
+
+pub struct Catalog {
+    // File name without the extension, e.g. `en`
+    pub code: String,
+    pub name: String,
+    // Font files tried, in order, for text the arcade fonts have no glyphs for
+    pub fonts: Vec<String>,
+    pub messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn load(path: &str) -> Result<Catalog, GameError> {
+        let code = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Catalog::parse(&KvFile::load(path)?, &code)
+    }
+
+    pub fn parse(file: &KvFile, code: &str) -> Result<Catalog, GameError> {
+        let mut catalog = Catalog {
+            code: code.to_string(),
+            name: code.to_string(),
+            fonts: Vec::new(),
+            messages: HashMap::new(),
+        };
+
+        for entry in &file.entries {
+            match (entry.section.as_str(), entry.key.as_str()) {
+                ("", "name") => catalog.name = entry.value.clone(),
+                ("", "fonts") => {
+                    catalog.fonts = entry
+                        .value
+                        .split(',')
+                        .map(|font| font.trim().to_string())
+                        .filter(|font| !font.is_empty())
+                        .collect()
+                }
+                ("messages", key) => {
+                    if catalog
+                        .messages
+                        .insert(key.to_string(), entry.value.clone())
+                        .is_some()
+                    {
+                        return Err(file.error(entry, &format!("`{key}` is defined twice")));
+                    }
+                }
+                _ => {
+                    return Err(file.error(
+                        entry,
+                        &format!("`{}` isn't something a catalog can have", entry.key),
+                    ))
+                }
+            }
+        }
+        Ok(catalog)
+    }
+}
+
+// All loaded catalogs with English first, which also fills in for missing messages
+pub struct Localization {
+    pub catalogs: Vec<Catalog>,
+    active: usize,
+}
+
+impl Localization {
+    // Every *.lang file in the directory. Broken files are reported and skipped, and the
+    // built-in English catalog is used when en.lang can't be loaded.
+    pub fn load_all(dir: &str) -> Localization {
+        let paths = assets::list(dir, "lang");
+        let mut catalogs: Vec<Catalog> = paths
+            .iter()
+            .filter_map(|path| match Catalog::load(path) {
+                Ok(catalog) => Some(catalog),
+                Err(e) => {
+                    eprintln!("{e}");
+                    None
+                }
+            })
+            .collect();
+        match catalogs
+            .iter()
+            .position(|catalog| catalog.code == FALLBACK_LANGUAGE)
+        {
+            Some(i) => {
+                let english = catalogs.remove(i);
+                catalogs.insert(0, english);
+            }
+            None => {
+                let file = KvFile::parse(DEFAULT_CATALOG, "en.lang")
+                    .expect("the built-in catalog is valid");
+                let english = Catalog::parse(&file, FALLBACK_LANGUAGE)
+                    .expect("the built-in catalog is valid");
+                catalogs.insert(0, english);
+            }
+        }
+
+        for (code, key) in missing_messages(&catalogs) {
+            eprintln!("{code}.lang has no `{key}`, showing English instead");
+        }
+        Localization {
+            catalogs,
+            active: 0,
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.active = index.min(self.catalogs.len() - 1);
+    }
+
+    pub fn language(&self) -> &Catalog {
+        &self.catalogs[self.active]
+    }
+
+    // The message in the selected language, then English, then the key itself so a
+    // missing message is easy to spot
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.language()
+            .messages
+            .get(key)
+            .or_else(|| self.catalogs[0].messages.get(key))
+            .map_or(key, |message| message.as_str())
+    }
+
+    // A message with its `{name}` placeholders filled in
+    pub fn format(&self, key: &str, values: &[(&str, &str)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in values {
+            message = message.replace(&format!("{{{name}}}"), value);
+        }
+        message
+    }
+}
+
+// (language code, key) for every English message a catalog doesn't translate
+pub fn missing_messages(catalogs: &[Catalog]) -> Vec<(String, String)> {
+    let Some(english) = catalogs
+        .iter()
+        .find(|catalog| catalog.code == FALLBACK_LANGUAGE)
+    else {
+        return Vec::new();
+    };
+    let mut keys: Vec<&String> = english.messages.keys().collect();
+    keys.sort();
+
+    let mut missing = Vec::new();
+    for catalog in catalogs {
+        for key in &keys {
+            if !catalog.messages.contains_key(*key) {
+                missing.push((catalog.code.clone(), key.to_string()));
+            }
+        }
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::I18N_PATH;
+
+    #[test]
+    fn every_shipped_catalog_has_every_key() {
+        let localization = Localization::load_all(I18N_PATH);
+        assert!(localization.catalogs.len() > 1);
+        assert_eq!(missing_messages(&localization.catalogs), Vec::new());
+
+        // Keys only a translation has are typos or leftovers
+        let english = &localization.catalogs[0];
+        for catalog in &localization.catalogs {
+            for key in catalog.messages.keys() {
+                assert!(
+                    english.messages.contains_key(key),
+                    "{}.lang has `{key}`, which en.lang doesn't",
+                    catalog.code
+                );
+            }
+        }
+    }
+}
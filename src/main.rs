@@ -1,74 +1,139 @@
 extern crate sdl2;
 
+use sdl2::controller::GameController;
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::messagebox::{show_simple_message_box, MessageBoxFlag};
+use std::path::PathBuf;
+use std::process;
 use std::time::Duration;
 
-use sdl2_snake::constants::{DOT_SIZE_IN_PXS, FRAMES_PER_SECOND, GRID_X_SIZE, GRID_Y_SIZE};
-use sdl2_snake::game_context::{GameContext, GameState, PlayerDirection};
+use sdl2_snake::assets;
+use sdl2_snake::audio::Audio;
+use sdl2_snake::constants::{FRAMES_PER_SECOND, SCREEN_HEIGHT, SCREEN_WIDTH};
+use sdl2_snake::error::GameError;
+use sdl2_snake::events::EventSubscriber;
+use sdl2_snake::game_context::GameContext;
+use sdl2_snake::input::InputAction;
 use sdl2_snake::renderer::Renderer;
 
-fn main() -> Result<(), String> {
-    let sdl_context = sdl2::init()?;
-    let video_subsys = sdl_context.video()?;
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{e}");
+        // Works without a window too, but there may be no display at all
+        let message = format!("The game has to close.\n\n{e}");
+        if let Err(box_error) =
+            show_simple_message_box(MessageBoxFlag::ERROR, "Snake", &message, None)
+        {
+            eprintln!("Could not show the error: {box_error}");
+        }
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), GameError> {
+    let sdl_context = sdl2::init().map_err(GameError::SdlInit)?;
+    let video_subsys = sdl_context.video().map_err(GameError::SdlInit)?;
+    let controller_subsys = sdl_context.game_controller().map_err(GameError::SdlInit)?;
 
     let window = video_subsys
-        .window(
-            "Snake Game SDL2 Rust",
-            GRID_X_SIZE * DOT_SIZE_IN_PXS,
-            GRID_Y_SIZE * DOT_SIZE_IN_PXS,
-        )
+        .window("Snake Game SDL2 Rust", SCREEN_WIDTH, SCREEN_HEIGHT)
         .position_centered()
+        .resizable()
+        .allow_highdpi()
         .opengl()
         .build()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| GameError::SdlInit(e.to_string()))?;
 
-    let mut event_pump = sdl_context.event_pump()?;
+    let mut event_pump = sdl_context.event_pump().map_err(GameError::SdlInit)?;
 
-    let mut context = GameContext::new();
+    // Controllers stop sending events once their handle is dropped
+    let mut controllers: Vec<GameController> = Vec::new();
 
-    let mut renderer = Renderer::new(window)?;
+    let mut context = GameContext::new();
 
-    let mut frame_counter = 0;
+    let ttf_context = sdl2::ttf::init().map_err(|e| GameError::SdlInit(e.to_string()))?;
+    let mut renderer = Renderer::new(window, &ttf_context)?;
+    let mut audio = Audio::new(&sdl_context);
 
     'running: loop {
         for event in event_pump.poll_iter() {
-            match event {
+            let action = match event {
                 Event::Quit { .. } => break 'running,
+                Event::KeyDown {
+                    keycode: Some(Keycode::F12),
+                    ..
+                } => {
+                    renderer.request_capture();
+                    None
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::F11),
+                    ..
+                } => {
+                    renderer.toggle_fullscreen()?;
+                    None
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    keymod,
+                    ..
+                } if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) => {
+                    renderer.toggle_fullscreen()?;
+                    None
+                }
                 Event::KeyDown {
                     keycode: Some(keycode),
                     ..
-                } => match keycode {
-                    Keycode::A | Keycode::Left => context.move_player(PlayerDirection::Left),
-                    Keycode::W | Keycode::Up => context.move_player(PlayerDirection::Up),
-                    Keycode::D | Keycode::Right => context.move_player(PlayerDirection::Right),
-                    Keycode::S | Keycode::Down => context.move_player(PlayerDirection::Down),
-                    Keycode::M => {
-                        if let GameState::Paused = context.state {
-                            context.toggle_mode()
-                        }
-                    }
-                    Keycode::Escape => {
-                        if let GameState::Over = context.state {
-                            context = GameContext::new()
-                        } else {
-                            context.toggle_pause()
-                        }
+                } => InputAction::from_keycode(keycode),
+                Event::ControllerButtonDown { button, .. } => {
+                    InputAction::from_controller_button(button)
+                }
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsys.open(which) {
+                        Ok(controller) => controllers.push(controller),
+                        Err(e) => eprintln!("Could not open controller {which}: {e}"),
                     }
-                    _ => {}
-                },
-                _ => {}
+                    None
+                }
+                _ => None,
+            };
+            if let Some(action) = action {
+                context.handle_input(action);
             }
         }
 
+        if context.quit_requested {
+            break 'running;
+        }
+
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / FRAMES_PER_SECOND));
 
-        frame_counter += 1;
-        if frame_counter % 10 == 0 {
-            context.do_next_tick();
+        context.update();
+        let events = context.take_events();
+        let subscribers: [&mut dyn EventSubscriber; 2] = [&mut renderer, &mut audio];
+        for subscriber in subscribers {
+            subscriber.handle_events(&events, &context);
         }
         renderer.draw(&context)?;
+        if let Some(mut frame) = renderer.take_capture() {
+            let path = screenshot_path();
+            frame.save_bmp(&path)?;
+            eprintln!("Saved {}", path.display());
+        }
     }
 
     Ok(())
 }
+
+// First free screenshot name in the data folder, so earlier captures are never overwritten
+fn screenshot_path() -> PathBuf {
+    let mut number = 1;
+    loop {
+        let path = assets::data_path(&format!("screenshot_{number}.bmp"));
+        if !path.exists() {
+            return path;
+        }
+        number += 1;
+    }
+}
@@ -0,0 +1,261 @@
+extern crate sdl2;
+
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas, SurfaceCanvas, Texture, TextureCreator, WindowCanvas};
+use sdl2::surface::{Surface, SurfaceContext};
+use sdl2::video::{FullscreenType, Window, WindowContext};
+
+use std::fs;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::constants::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::error::GameError;
+
+// Drawing operations the Renderer needs, so frames can go to a window or to memory
+pub trait RenderBackend {
+    fn set_draw_color(&mut self, color: Color);
+    fn clear(&mut self);
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), GameError>;
+    fn draw_line(&mut self, start: (i32, i32), end: (i32, i32)) -> Result<(), GameError>;
+    fn copy(&mut self, texture: &Texture, src: Option<Rect>, dst: Rect) -> Result<(), GameError>;
+    fn copy_rotated(
+        &mut self,
+        texture: &Texture,
+        src: Option<Rect>,
+        dst: Rect,
+        angle: f64,
+    ) -> Result<(), GameError>;
+    fn create_texture_from_surface(&self, surface: &Surface) -> Result<Texture, GameError>;
+    fn present(&mut self);
+    // The frame drawn so far, must be called before present()
+    fn read_frame(&self) -> Result<Frame, GameError>;
+    // Size in physical pixels, larger than the logical screen when scaled up
+    fn output_size(&self) -> Result<(u32, u32), GameError>;
+}
+
+// A captured frame as tightly packed RGBA bytes
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let i = ((y * self.width + x) * 4) as usize;
+        Color::RGBA(
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        )
+    }
+
+    // Creates the folder when it's missing
+    pub fn save_bmp(&mut self, path: &Path) -> Result<(), GameError> {
+        let error = |message: String| GameError::SaveFile {
+            path: path.to_path_buf(),
+            message,
+        };
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| error(e.to_string()))?;
+        let (width, height) = (self.width, self.height);
+        let surface = Surface::from_data(&mut self.pixels, width, height, width * 4, FRAME_FORMAT)
+            .map_err(GameError::Render)?;
+        surface.save_bmp(path).map_err(error)
+    }
+}
+
+// Byte order R, G, B, A in memory on little endian machines
+const FRAME_FORMAT: PixelFormatEnum = PixelFormatEnum::ABGR8888;
+
+pub struct CanvasBackend {
+    canvas: WindowCanvas,
+    texture_creator: TextureCreator<WindowContext>,
+}
+
+impl CanvasBackend {
+    // Everything is drawn at the logical screen size and scaled to fit the window,
+    // with black bars where the aspect ratio doesn't match
+    pub fn new(window: Window) -> Result<CanvasBackend, GameError> {
+        let mut canvas = window
+            .into_canvas()
+            .build()
+            .map_err(|e| GameError::SdlInit(e.to_string()))?;
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas
+            .set_logical_size(SCREEN_WIDTH, SCREEN_HEIGHT)
+            .map_err(|e| GameError::SdlInit(e.to_string()))?;
+        let texture_creator = canvas.texture_creator();
+        Ok(CanvasBackend {
+            canvas,
+            texture_creator,
+        })
+    }
+}
+
+impl CanvasBackend {
+    // Switch between a window and borderless fullscreen at the desktop resolution
+    pub fn toggle_fullscreen(&mut self) -> Result<(), GameError> {
+        let window = self.canvas.window_mut();
+        let fullscreen = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        window.set_fullscreen(fullscreen).map_err(GameError::Render)
+    }
+}
+
+impl RenderBackend for CanvasBackend {
+    fn set_draw_color(&mut self, color: Color) {
+        self.canvas.set_draw_color(color);
+    }
+
+    fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), GameError> {
+        self.canvas.fill_rect(rect).map_err(GameError::Render)
+    }
+
+    fn draw_line(&mut self, start: (i32, i32), end: (i32, i32)) -> Result<(), GameError> {
+        self.canvas.draw_line(start, end).map_err(GameError::Render)
+    }
+
+    fn copy(&mut self, texture: &Texture, src: Option<Rect>, dst: Rect) -> Result<(), GameError> {
+        self.canvas
+            .copy(texture, src, Some(dst))
+            .map_err(GameError::Render)
+    }
+
+    fn copy_rotated(
+        &mut self,
+        texture: &Texture,
+        src: Option<Rect>,
+        dst: Rect,
+        angle: f64,
+    ) -> Result<(), GameError> {
+        self.canvas
+            .copy_ex(texture, src, Some(dst), angle, None, false, false)
+            .map_err(GameError::Render)
+    }
+
+    fn create_texture_from_surface(&self, surface: &Surface) -> Result<Texture, GameError> {
+        self.texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(|e| GameError::Render(e.to_string()))
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+    }
+
+    fn read_frame(&self) -> Result<Frame, GameError> {
+        read_canvas_frame(&self.canvas)
+    }
+
+    fn output_size(&self) -> Result<(u32, u32), GameError> {
+        self.canvas.output_size().map_err(GameError::Render)
+    }
+}
+
+// Renders into a software surface, needs neither a display nor a GPU
+pub struct SoftwareBackend {
+    canvas: SurfaceCanvas<'static>,
+    texture_creator: TextureCreator<SurfaceContext<'static>>,
+}
+
+impl SoftwareBackend {
+    pub fn new(width: u32, height: u32) -> Result<SoftwareBackend, GameError> {
+        let surface =
+            Surface::new(width, height, PixelFormatEnum::RGBA8888).map_err(GameError::SdlInit)?;
+        let mut canvas = Canvas::from_surface(surface).map_err(GameError::SdlInit)?;
+        canvas.set_blend_mode(BlendMode::Blend);
+        let texture_creator = canvas.texture_creator();
+        Ok(SoftwareBackend {
+            canvas,
+            texture_creator,
+        })
+    }
+}
+
+impl RenderBackend for SoftwareBackend {
+    fn set_draw_color(&mut self, color: Color) {
+        self.canvas.set_draw_color(color);
+    }
+
+    fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), GameError> {
+        self.canvas.fill_rect(rect).map_err(GameError::Render)
+    }
+
+    fn draw_line(&mut self, start: (i32, i32), end: (i32, i32)) -> Result<(), GameError> {
+        self.canvas.draw_line(start, end).map_err(GameError::Render)
+    }
+
+    fn copy(&mut self, texture: &Texture, src: Option<Rect>, dst: Rect) -> Result<(), GameError> {
+        self.canvas
+            .copy(texture, src, Some(dst))
+            .map_err(GameError::Render)
+    }
+
+    fn copy_rotated(
+        &mut self,
+        texture: &Texture,
+        src: Option<Rect>,
+        dst: Rect,
+        angle: f64,
+    ) -> Result<(), GameError> {
+        self.canvas
+            .copy_ex(texture, src, Some(dst), angle, None, false, false)
+            .map_err(GameError::Render)
+    }
+
+    fn create_texture_from_surface(&self, surface: &Surface) -> Result<Texture, GameError> {
+        self.texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(|e| GameError::Render(e.to_string()))
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+    }
+
+    fn read_frame(&self) -> Result<Frame, GameError> {
+        read_canvas_frame(&self.canvas)
+    }
+
+    fn output_size(&self) -> Result<(u32, u32), GameError> {
+        self.canvas.output_size().map_err(GameError::Render)
+    }
+}
+
+// Reads only the viewport, without the black bars around a letterboxed screen. SDL reports the
+// viewport in logical pixels but reads physical ones.
+fn read_canvas_frame<T: sdl2::render::RenderTarget>(
+    canvas: &Canvas<T>,
+) -> Result<Frame, GameError> {
+    let viewport = canvas.viewport();
+    let (scale_x, scale_y) = canvas.scale();
+    let rect = Rect::new(
+        (viewport.x() as f32 * scale_x).round() as i32,
+        (viewport.y() as f32 * scale_y).round() as i32,
+        (viewport.width() as f32 * scale_x).round() as u32,
+        (viewport.height() as f32 * scale_y).round() as u32,
+    );
+    let pixels = canvas
+        .read_pixels(rect, FRAME_FORMAT)
+        .map_err(GameError::Render)?;
+    Ok(Frame {
+        width: rect.width(),
+        height: rect.height(),
+        pixels,
+    })
+}
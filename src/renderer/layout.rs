@@ -0,0 +1,252 @@
+extern crate sdl2;
+
+use std::collections::HashMap;
+
+use sdl2::rect::Rect;
+
+use crate::entities::text_elements::{FontDefinition, TextAlignment, TextElement};
+use crate::error::GameError;
+
+// Keycaps are this much wider than their text on each side
+pub const KEY_PADDING: u32 = 4;
+
+// A styled part of a line with its font already resolved
+#[derive(Clone)]
+pub struct StyledRun {
+    pub text: String,
+    pub font: FontDefinition,
+    pub is_key: bool,
+}
+
+pub struct PlacedRun {
+    pub cache_key: String,
+    pub text: String,
+    pub font: FontDefinition,
+    pub is_key: bool,
+    pub rect: Rect,
+}
+
+// One line of an element ready to draw. Wrapped lines become several of these.
+pub struct PlacedLine {
+    pub rect: Rect,
+    pub runs: Vec<PlacedRun>,
+}
+
+pub struct ElementLayout {
+    pub bounds: Rect,
+    pub lines: Vec<PlacedLine>,
+}
+
+// A line of the element with its runs already resolved
+pub struct LayoutInput<'a> {
+    pub key: &'a str,
+    pub order: u32,
+    pub runs: Vec<StyledRun>,
+    pub alignment: &'a TextAlignment,
+    pub offset: (i32, i32),
+}
+
+// Place the element inside `reference`, the screen or its parent's box. `measure` gives the
+// size of a text in logical pixels.
+pub fn layout_element<M>(
+    element: &TextElement,
+    mut lines: Vec<LayoutInput>,
+    reference: Rect,
+    mut measure: M,
+) -> Result<ElementLayout, GameError>
+where
+    M: FnMut(&str, &FontDefinition) -> Result<(u32, u32), GameError>,
+{
+    lines.sort_by(|a, b| a.order.cmp(&b.order).then(a.key.cmp(b.key)));
+
+    // Break up the lines first, the box is as big as the widest piece
+    let mut pieces: Vec<Piece> = Vec::new();
+    for line in &lines {
+        let wrapped = match element.max_width {
+            Some(max_width) => wrap_runs(&line.runs, max_width, &mut measure)?,
+            None => vec![line.runs.clone()],
+        };
+        for runs in wrapped {
+            let sizes = run_sizes(&runs, &mut measure)?;
+            let size = (
+                sizes.iter().map(|size| size.0).sum(),
+                sizes.iter().map(|size| size.1).max().unwrap_or(0),
+            );
+            pieces.push(Piece {
+                runs: runs.into_iter().zip(sizes).collect(),
+                line,
+                size,
+            });
+        }
+    }
+
+    let content_width = pieces.iter().map(|p| p.size.0).max().unwrap_or(0) as i32;
+    let content_height = pieces.iter().map(|p| p.size.1 as i32).sum::<i32>()
+        + element.line_spacing * (pieces.len() as i32 - 1).max(0);
+    let width = content_width + element.padding * 2;
+    let height = content_height + element.padding * 2;
+
+    let (fraction_x, fraction_y) = element.anchor.fraction();
+    // Offsets push away from the edge the element sits against
+    let direction = |fraction: f32| if fraction > 0.5 { -1 } else { 1 };
+    let x = reference.x() + (reference.width() as f32 * fraction_x) as i32
+        - (width as f32 * fraction_x) as i32
+        + element.position.x * direction(fraction_x);
+    let y = reference.y() + (reference.height() as f32 * fraction_y) as i32
+        - (height as f32 * fraction_y) as i32
+        + element.position.y * direction(fraction_y);
+    let bounds = Rect::new(x, y, width.max(0) as u32, height.max(0) as u32);
+
+    let mut placed = Vec::new();
+    let mut line_y = y + element.padding;
+    let mut piece_index: HashMap<&str, usize> = HashMap::new();
+    for piece in pieces {
+        let (line_width, line_height) = piece.size;
+        let (offset_x, offset_y) = piece.line.offset;
+        let line_x = match piece.line.alignment {
+            TextAlignment::Start => x + element.padding,
+            TextAlignment::Center => x + (width - line_width as i32) / 2,
+            TextAlignment::End => x + width - element.padding - line_width as i32,
+        } + offset_x;
+        let line_top = line_y + offset_y;
+
+        // The first run keeps the line's key so unstyled lines cache as before
+        let index = piece_index.entry(piece.line.key).or_insert(0);
+        let mut runs = Vec::new();
+        let mut run_x = line_x;
+        for (j, (run, (run_width, run_height))) in piece.runs.into_iter().enumerate() {
+            let cache_key = if *index == 0 && j == 0 {
+                piece.line.key.to_string()
+            } else {
+                format!("{}#{}.{j}", piece.line.key, *index)
+            };
+            // Runs of different sizes share a bottom edge
+            let rect = Rect::new(
+                run_x,
+                line_top + (line_height - run_height) as i32,
+                run_width,
+                run_height,
+            );
+            run_x += run_width as i32;
+            runs.push(PlacedRun {
+                cache_key,
+                text: run.text,
+                font: run.font,
+                is_key: run.is_key,
+                rect,
+            });
+        }
+        *index += 1;
+
+        placed.push(PlacedLine {
+            rect: Rect::new(line_x, line_top, line_width, line_height),
+            runs,
+        });
+        line_y += line_height as i32 + element.line_spacing;
+    }
+
+    Ok(ElementLayout {
+        bounds,
+        lines: placed,
+    })
+}
+
+struct Piece<'a> {
+    runs: Vec<(StyledRun, (u32, u32))>,
+    line: &'a LayoutInput<'a>,
+    size: (u32, u32),
+}
+
+fn run_sizes<M>(runs: &[StyledRun], measure: &mut M) -> Result<Vec<(u32, u32)>, GameError>
+where
+    M: FnMut(&str, &FontDefinition) -> Result<(u32, u32), GameError>,
+{
+    runs.iter()
+        .map(|run| {
+            let (width, height) = measure(&run.text, &run.font)?;
+            let padding = if run.is_key { KEY_PADDING * 2 } else { 0 };
+            Ok((width + padding, height))
+        })
+        .collect()
+}
+
+// Greedy word wrap across runs, a word wider than the limit gets a piece of its own
+fn wrap_runs<M>(
+    runs: &[StyledRun],
+    max_width: u32,
+    measure: &mut M,
+) -> Result<Vec<Vec<StyledRun>>, GameError>
+where
+    M: FnMut(&str, &FontDefinition) -> Result<(u32, u32), GameError>,
+{
+    // Words are lists of runs so a styled part in the middle of a word stays attached
+    let mut words: Vec<Vec<StyledRun>> = vec![Vec::new()];
+    for run in runs {
+        if run.is_key {
+            words.last_mut().unwrap().push(run.clone());
+            continue;
+        }
+        for (i, part) in run.text.split(' ').enumerate() {
+            if i > 0 {
+                words.push(Vec::new());
+            }
+            if !part.is_empty() {
+                push_run(
+                    words.last_mut().unwrap(),
+                    StyledRun {
+                        text: part.to_string(),
+                        ..run.clone()
+                    },
+                );
+            }
+        }
+    }
+    words.retain(|word| !word.is_empty());
+
+    let mut wrapped = Vec::new();
+    let mut current: Vec<StyledRun> = Vec::new();
+    for word in words {
+        let mut candidate = current.clone();
+        if let Some(last) = candidate.last() {
+            let space = StyledRun {
+                text: " ".to_string(),
+                font: last.font,
+                is_key: false,
+            };
+            push_run(&mut candidate, space);
+        }
+        for run in word.iter().cloned() {
+            push_run(&mut candidate, run);
+        }
+        let width: u32 = run_sizes(&candidate, measure)?
+            .iter()
+            .map(|size| size.0)
+            .sum();
+        if !current.is_empty() && width > max_width {
+            wrapped.push(std::mem::replace(&mut current, word));
+        } else {
+            current = candidate;
+        }
+    }
+    // An empty line still takes up a line's height
+    if current.is_empty() {
+        if let Some(first) = runs.first() {
+            current.push(StyledRun {
+                text: String::new(),
+                ..first.clone()
+            });
+        }
+    }
+    wrapped.push(current);
+    Ok(wrapped)
+}
+
+// Runs with the same font are joined so each is rendered in one go
+fn push_run(runs: &mut Vec<StyledRun>, run: StyledRun) {
+    match runs.last_mut() {
+        Some(last) if !last.is_key && !run.is_key && last.font == run.font => {
+            last.text.push_str(&run.text)
+        }
+        _ => runs.push(run),
+    }
+}
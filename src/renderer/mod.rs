@@ -0,0 +1,793 @@
+extern crate sdl2;
+
+pub mod backend;
+pub mod layout;
+pub mod particles;
+pub mod sprites;
+pub mod text_cache;
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::color::Color;
+use crate::constants::{DOT_SIZE_IN_PXS, SCREEN_HEIGHT, SCREEN_WIDTH, SPRITE_ATLAS_PATH};
+use crate::entities::death_animation::DeathAnimation;
+use crate::entities::rich_text::{self, RunColor, RunStyle, TextRun};
+use crate::entities::terrain::TerrainTile;
+use crate::entities::text_elements::FontDefinition;
+use crate::entities::text_template::GameVariables;
+use crate::error::GameError;
+use crate::events::{EventSubscriber, GameEvent};
+use crate::game_context::{GameContext, GameState, PlayerDirection, Point};
+use crate::theme::Theme;
+use sdl2::rect::Rect;
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::Window;
+
+use backend::{CanvasBackend, Frame, RenderBackend, SoftwareBackend};
+use layout::{layout_element, ElementLayout, LayoutInput, StyledRun, KEY_PADDING};
+use particles::ParticleSystem;
+use sprites::{snake_sprites, SpriteAtlas, SpriteKind};
+use text_cache::{RenderMetrics, TextCache};
+
+pub struct Renderer<'ttf, B: RenderBackend> {
+    backend: B,
+    ttf_context: &'ttf Sdl2TtfContext,
+    text_cache: TextCache<'ttf>,
+    // Plain rectangles are drawn when the atlas can't be loaded
+    sprites: Option<SpriteAtlas>,
+    // Screen shake applied to everything drawn on the board
+    board_offset: (i32, i32),
+    // Physical pixels per logical pixel, text is rendered at this size to stay sharp
+    text_scale: f32,
+    particles: ParticleSystem,
+    // Set by request_capture, the next draw keeps a copy of the frame before presenting it
+    capture_requested: bool,
+    captured: Option<Frame>,
+}
+
+impl<'ttf> Renderer<'ttf, CanvasBackend> {
+    pub fn new(
+        window: Window,
+        ttf_context: &'ttf Sdl2TtfContext,
+    ) -> Result<Renderer<'ttf, CanvasBackend>, GameError> {
+        Ok(Renderer::with_backend(
+            CanvasBackend::new(window)?,
+            ttf_context,
+        ))
+    }
+
+    pub fn toggle_fullscreen(&mut self) -> Result<(), GameError> {
+        self.backend.toggle_fullscreen()
+    }
+}
+
+impl<'ttf> Renderer<'ttf, SoftwareBackend> {
+    // Offscreen renderer the size of the game window, for screenshots and tests
+    pub fn new_headless(
+        ttf_context: &'ttf Sdl2TtfContext,
+    ) -> Result<Renderer<'ttf, SoftwareBackend>, GameError> {
+        let backend = SoftwareBackend::new(SCREEN_WIDTH, SCREEN_HEIGHT)?;
+        Ok(Renderer::with_backend(backend, ttf_context))
+    }
+}
+
+impl<'ttf, B: RenderBackend> Renderer<'ttf, B> {
+    pub fn with_backend(backend: B, ttf_context: &'ttf Sdl2TtfContext) -> Renderer<'ttf, B> {
+        let sprites = match SpriteAtlas::load(&backend, SPRITE_ATLAS_PATH, DOT_SIZE_IN_PXS) {
+            Ok(atlas) => Some(atlas),
+            Err(e) => {
+                eprintln!("Sprites unavailable, drawing plain shapes: {e}");
+                None
+            }
+        };
+        Renderer {
+            backend,
+            ttf_context,
+            text_cache: TextCache::new(),
+            sprites,
+            board_offset: (0, 0),
+            text_scale: 1.0,
+            particles: ParticleSystem::new(),
+            capture_requested: false,
+            captured: None,
+        }
+    }
+
+    // Capture the next frame drawn. The back buffer is undefined once presented, so it's read
+    // at the end of draw() and picked up with take_capture().
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+
+    pub fn take_capture(&mut self) -> Option<Frame> {
+        self.captured.take()
+    }
+
+    pub fn metrics(&self) -> &RenderMetrics {
+        &self.text_cache.metrics
+    }
+
+    // Letterboxing keeps the aspect ratio, so the smaller ratio is the one applied
+    fn output_scale(&self) -> Result<f32, GameError> {
+        let (width, height) = self.backend.output_size()?;
+        let scale = (width as f32 / SCREEN_WIDTH as f32).min(height as f32 / SCREEN_HEIGHT as f32);
+        Ok(scale.max(1.0))
+    }
+
+    pub fn draw(&mut self, context: &GameContext) -> Result<(), GameError> {
+        let frame_start = Instant::now();
+        self.text_scale = self.output_scale()?;
+        self.board_offset = match &context.death {
+            Some(death) => death.shake_offset(),
+            None => (0, 0),
+        };
+
+        self.draw_bg(context);
+        self.draw_terrain(context)?;
+        self.draw_player(context)?;
+        self.draw_food(context)?;
+        self.draw_power_up(context)?;
+        self.draw_actors(context)?;
+        self.particles.update();
+        self.particles.draw(&mut self.backend, self.board_offset)?;
+        self.draw_text_elements(context)?;
+        self.draw_transition(context)?;
+        if self.capture_requested {
+            self.capture_requested = false;
+            self.captured = Some(self.backend.read_frame()?);
+        }
+        self.backend.present();
+
+        let metrics = &mut self.text_cache.metrics;
+        metrics.frames += 1;
+        metrics.last_frame_time = frame_start.elapsed();
+        metrics.total_frame_time += metrics.last_frame_time;
+
+        Ok(())
+    }
+
+    fn draw_bg(&mut self, context: &GameContext) {
+        let theme = context.theme();
+        let color = match (context.state, &context.death) {
+            (GameState::Dying, Some(death)) if death.is_flash_on() => theme.death_flash,
+            (state, _) => theme.background(state),
+        };
+
+        self.backend.set_draw_color(color);
+        self.backend.clear();
+    }
+
+    fn draw_terrain(&mut self, context: &GameContext) -> Result<(), GameError> {
+        let theme = context.theme();
+        for (point, tile) in context.terrain.tiles() {
+            let Some(color) = theme.terrain(tile) else {
+                continue;
+            };
+            self.backend.set_draw_color(color);
+            self.draw_point(&point)?;
+
+            // Mark the direction conveyors push and gates let through
+            match tile {
+                TerrainTile::Conveyor(direction) | TerrainTile::OneWayGate(direction) => {
+                    self.backend.set_draw_color(theme.terrain_marker);
+                    let size = DOT_SIZE_IN_PXS as i32;
+                    self.draw_direction_marker((point.0 * size, point.1 * size), direction)?;
+                }
+                TerrainTile::BreakableWall => {
+                    self.backend.set_draw_color(theme.wall_detail);
+                    let Point(x, y) = point;
+                    let size = DOT_SIZE_IN_PXS as i32;
+                    let (offset_x, offset_y) = self.board_offset;
+                    self.backend.draw_line(
+                        (x * size + offset_x, y * size + size / 2 + offset_y),
+                        (
+                            x * size + size - 1 + offset_x,
+                            y * size + size / 2 + offset_y,
+                        ),
+                    )?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Bar along one side of the cell whose top left corner is at `cell` in pixels
+    fn draw_direction_marker(
+        &mut self,
+        cell: (i32, i32),
+        direction: PlayerDirection,
+    ) -> Result<(), GameError> {
+        let (x, y) = cell;
+        let size = DOT_SIZE_IN_PXS as i32;
+        let thickness = DOT_SIZE_IN_PXS / 4;
+        let mut marker = match direction {
+            PlayerDirection::Up => Rect::new(x, y, DOT_SIZE_IN_PXS, thickness),
+            PlayerDirection::Down => {
+                Rect::new(x, y + size - thickness as i32, DOT_SIZE_IN_PXS, thickness)
+            }
+            PlayerDirection::Left => Rect::new(x, y, thickness, DOT_SIZE_IN_PXS),
+            PlayerDirection::Right => {
+                Rect::new(x + size - thickness as i32, y, thickness, DOT_SIZE_IN_PXS)
+            }
+        };
+        marker.offset(self.board_offset.0, self.board_offset.1);
+        self.backend.fill_rect(marker)?;
+        Ok(())
+    }
+
+    fn draw_power_up(&mut self, context: &GameContext) -> Result<(), GameError> {
+        if let Some(power_up) = &context.power_up {
+            self.backend.set_draw_color(context.theme().power_up);
+            if context.settings.shape_markers {
+                self.draw_ring(power_up)?;
+            } else {
+                self.draw_point(power_up)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_player(&mut self, context: &GameContext) -> Result<(), GameError> {
+        if context.state == GameState::Over {
+            return Ok(());
+        }
+        if let (GameState::Dying, Some(death)) = (context.state, &context.death) {
+            return self.draw_dying_player(death, context.theme());
+        }
+
+        let theme = context.theme();
+        let length = context.player_position.len();
+        // The snake takes a single color while it can break walls
+        let colors: Vec<Color> = (0..length)
+            .map(|i| {
+                if context.has_wall_breaker {
+                    theme.snake_wall_breaker
+                } else {
+                    theme.snake_segment(i, length)
+                }
+            })
+            .collect();
+
+        let size = DOT_SIZE_IN_PXS as i32;
+        let Point(head_x, head_y) = context.player_position[0];
+        let mut head = (head_x * size, head_y * size);
+        if context.settings.smooth_movement {
+            head = self.draw_smooth_player(context, &colors)?;
+        } else if self.sprites.is_some() {
+            let sprites = snake_sprites(&context.player_position, context.player_direction);
+            for (i, (point, (kind, angle))) in
+                context.player_position.iter().zip(sprites).enumerate()
+            {
+                self.draw_tinted_sprite(point, kind, angle, colors[i])?;
+            }
+        } else {
+            for (point, color) in context.player_position.iter().zip(&colors) {
+                self.backend.set_draw_color(*color);
+                self.draw_point(point)?;
+            }
+        }
+
+        // A bar on the side the head faces, so it stands out without relying on color
+        if context.settings.shape_markers {
+            self.backend.set_draw_color(theme.background_playing);
+            self.draw_direction_marker(head, context.player_direction)?;
+        }
+        Ok(())
+    }
+
+    // Slides the snake between its previous and current cells as a rounded tube. The path
+    // runs from the head through the cells the body occupies to the tail, so every bend
+    // gets a round corner. Returns the pixel position of the head.
+    fn draw_smooth_player(
+        &mut self,
+        context: &GameContext,
+        colors: &[Color],
+    ) -> Result<(i32, i32), GameError> {
+        let current = &context.player_position;
+        let previous = &context.previous_player_position;
+        let progress = context.tick_progress();
+        let sliding = |i: usize| -> (f32, f32) {
+            let to = current[i];
+            let from = previous.get(i).copied().unwrap_or(to);
+            // Jumps across the board edge in Wall Pass aren't slid
+            if (to.0 - from.0).abs() + (to.1 - from.1).abs() != 1 {
+                return (to.0 as f32, to.1 as f32);
+            }
+            (
+                from.0 as f32 + (to.0 - from.0) as f32 * progress,
+                from.1 as f32 + (to.1 - from.1) as f32 * progress,
+            )
+        };
+
+        let last = current.len() - 1;
+        let mut path = vec![(sliding(0), colors[0])];
+        for i in 1..=last {
+            path.push(((current[i].0 as f32, current[i].1 as f32), colors[i]));
+        }
+        path.push((sliding(last), colors[last]));
+
+        let size = DOT_SIZE_IN_PXS as f32;
+        let radius = DOT_SIZE_IN_PXS as i32 * 9 / 20;
+        let (offset_x, offset_y) = self.board_offset;
+        let to_pixels = |(x, y): (f32, f32)| {
+            (
+                (x * size + size / 2.0).round() as i32 + offset_x,
+                (y * size + size / 2.0).round() as i32 + offset_y,
+            )
+        };
+
+        // Tail first so the head ends up on top
+        for pair in path.windows(2).rev() {
+            let ((from, _), (to, color)) = (pair[0], pair[1]);
+            self.backend.set_draw_color(color);
+            if (from.0 - to.0).abs() + (from.1 - to.1).abs() <= 1.0 {
+                let (x1, y1) = to_pixels(from);
+                let (x2, y2) = to_pixels(to);
+                self.backend.fill_rect(Rect::new(
+                    x1.min(x2) - radius,
+                    y1.min(y2) - radius,
+                    ((x1 - x2).abs() + radius * 2) as u32,
+                    ((y1 - y2).abs() + radius * 2) as u32,
+                ))?;
+            }
+            self.fill_circle(to_pixels(to), radius)?;
+        }
+        self.backend.set_draw_color(colors[0]);
+        self.fill_circle(to_pixels(path[0].0), radius)?;
+
+        let (x, y) = path[0].0;
+        Ok(((x * size).round() as i32, (y * size).round() as i32))
+    }
+
+    fn fill_circle(&mut self, center: (i32, i32), radius: i32) -> Result<(), GameError> {
+        let (cx, cy) = center;
+        for dy in -radius..=radius {
+            let reach = ((radius * radius - dy * dy) as f32).sqrt().round() as i32;
+            self.backend
+                .draw_line((cx - reach, cy + dy), (cx + reach, cy + dy))?;
+        }
+        Ok(())
+    }
+
+    // Atlas tiles are greyscale so the theme colors them
+    fn draw_tinted_sprite(
+        &mut self,
+        point: &Point,
+        kind: SpriteKind,
+        angle: f64,
+        tint: Color,
+    ) -> Result<(), GameError> {
+        if let Some(atlas) = &mut self.sprites {
+            atlas.texture.set_color_mod(tint.r, tint.g, tint.b);
+            let Point(x, y) = point;
+            let (offset_x, offset_y) = self.board_offset;
+            let target = Rect::new(
+                x * DOT_SIZE_IN_PXS as i32 + offset_x,
+                y * DOT_SIZE_IN_PXS as i32 + offset_y,
+                DOT_SIZE_IN_PXS,
+                DOT_SIZE_IN_PXS,
+            );
+            self.backend
+                .copy_rotated(&atlas.texture, Some(atlas.source(kind)), target, angle)?;
+        }
+        Ok(())
+    }
+
+    fn draw_dying_player(
+        &mut self,
+        death: &DeathAnimation,
+        theme: &Theme,
+    ) -> Result<(), GameError> {
+        let color = if death.is_scattering() {
+            Color::RGBA(
+                theme.snake_head.r,
+                theme.snake_head.g,
+                theme.snake_head.b,
+                death.segment_alpha(),
+            )
+        } else if death.is_flash_on() {
+            theme.snake_death_flash
+        } else {
+            theme.snake_head
+        };
+        self.backend.set_draw_color(color);
+
+        let (offset_x, offset_y) = self.board_offset;
+        for segment in &death.segments {
+            self.backend.fill_rect(Rect::new(
+                (segment.x * DOT_SIZE_IN_PXS as f32) as i32 + offset_x,
+                (segment.y * DOT_SIZE_IN_PXS as f32) as i32 + offset_y,
+                DOT_SIZE_IN_PXS,
+                DOT_SIZE_IN_PXS,
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn draw_food(&mut self, context: &GameContext) -> Result<(), GameError> {
+        let color = context.theme().food;
+        if context.settings.shape_markers {
+            self.backend.set_draw_color(color);
+            return self.draw_diamond(&context.food);
+        }
+        if self.sprites.is_some() {
+            return self.draw_tinted_sprite(&context.food, SpriteKind::Food, 0.0, color);
+        }
+        self.backend.set_draw_color(color);
+        self.draw_point(&context.food)?;
+        Ok(())
+    }
+
+    fn draw_actors(&mut self, context: &GameContext) -> Result<(), GameError> {
+        for actor in &context.actors {
+            if !actor.alive {
+                continue;
+            }
+            self.backend
+                .set_draw_color(context.theme().actor(actor.kind));
+            self.draw_point(&actor.position)?;
+        }
+        Ok(())
+    }
+
+    fn draw_point(&mut self, point: &Point) -> Result<(), GameError> {
+        let Point(x, y) = point;
+        let (offset_x, offset_y) = self.board_offset;
+        self.backend.fill_rect(Rect::new(
+            x * DOT_SIZE_IN_PXS as i32 + offset_x,
+            y * DOT_SIZE_IN_PXS as i32 + offset_y,
+            DOT_SIZE_IN_PXS,
+            DOT_SIZE_IN_PXS,
+        ))?;
+
+        Ok(())
+    }
+
+    // Hollow square filling the cell
+    fn draw_ring(&mut self, point: &Point) -> Result<(), GameError> {
+        let Point(x, y) = point;
+        let size = DOT_SIZE_IN_PXS as i32;
+        let thickness = DOT_SIZE_IN_PXS / 4;
+        let (left, top) = (
+            x * size + self.board_offset.0,
+            y * size + self.board_offset.1,
+        );
+        for edge in [
+            Rect::new(left, top, DOT_SIZE_IN_PXS, thickness),
+            Rect::new(
+                left,
+                top + size - thickness as i32,
+                DOT_SIZE_IN_PXS,
+                thickness,
+            ),
+            Rect::new(left, top, thickness, DOT_SIZE_IN_PXS),
+            Rect::new(
+                left + size - thickness as i32,
+                top,
+                thickness,
+                DOT_SIZE_IN_PXS,
+            ),
+        ] {
+            self.backend.fill_rect(edge)?;
+        }
+        Ok(())
+    }
+
+    // Diamond touching the middle of each side of the cell
+    fn draw_diamond(&mut self, point: &Point) -> Result<(), GameError> {
+        let Point(x, y) = point;
+        let size = DOT_SIZE_IN_PXS as i32;
+        let half = size / 2;
+        let (left, top) = (
+            x * size + self.board_offset.0,
+            y * size + self.board_offset.1,
+        );
+        for row in 1..size {
+            let reach = half - (row - half).abs();
+            self.backend.draw_line(
+                (left + half - reach, top + row),
+                (left + half + reach - 1, top + row),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_text_elements(&mut self, context: &GameContext) -> Result<(), GameError> {
+        self.text_cache.prune(&context.text_elements);
+        self.text_cache
+            .set_language_fonts(&context.localization.language().fonts);
+
+        let mut visible_keys = context
+            .text_elements
+            .iter()
+            .filter(|(_, element)| element.visible)
+            .map(|(key, _)| key)
+            .collect::<Vec<&String>>();
+        visible_keys.sort_by_key(|key| context.text_elements[*key].draw_order);
+
+        let theme = context.theme();
+        let variables = context.variables();
+        let mut layouts: HashMap<String, ElementLayout> = HashMap::new();
+        for element_key in visible_keys {
+            self.layout_text_element(element_key, context, &variables, &mut layouts)?;
+            let element = &context.text_elements[element_key];
+            let layout = &layouts[element_key];
+
+            if element.is_overlay {
+                self.create_overlay(theme.overlay)?;
+            }
+            if let Some(background) = element.background {
+                self.backend.set_draw_color(background);
+                self.backend.fill_rect(layout.bounds)?;
+            }
+
+            let animation = &element.animation;
+            let (offset_x, offset_y) = animation.offset();
+            let scale = animation.scale();
+            for line in &layout.lines {
+                // Scaling grows the line around its center
+                let (center_x, center_y) = (
+                    line.rect.x() as f32 + line.rect.width() as f32 / 2.0,
+                    line.rect.y() as f32 + line.rect.height() as f32 / 2.0,
+                );
+                for run in &line.runs {
+                    let target = Rect::new(
+                        (center_x + (run.rect.x() as f32 - center_x) * scale).round() as i32
+                            + offset_x,
+                        (center_y + (run.rect.y() as f32 - center_y) * scale).round() as i32
+                            + offset_y,
+                        (run.rect.width() as f32 * scale).round() as u32,
+                        (run.rect.height() as f32 * scale).round() as u32,
+                    );
+                    let text_target = if run.is_key {
+                        let mut keycap = run.font.color;
+                        keycap.a = animation.alpha();
+                        self.draw_outline(target, keycap)?;
+                        let inset = (KEY_PADDING as f32 * scale).round() as i32;
+                        Rect::new(
+                            target.x() + inset,
+                            target.y(),
+                            target.width().saturating_sub(inset as u32 * 2),
+                            target.height(),
+                        )
+                    } else {
+                        target
+                    };
+
+                    // SDL_ttf can't render empty text, the line still takes up its space
+                    if run.text.is_empty() {
+                        continue;
+                    }
+                    let cached = self.text_cache.get_text(
+                        self.ttf_context,
+                        &self.backend,
+                        element_key,
+                        &run.cache_key,
+                        &run.text,
+                        &run.font,
+                    )?;
+                    cached.texture.set_alpha_mod(animation.alpha());
+                    self.backend.copy(&cached.texture, None, text_target)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Lays out an element after the parents it's anchored to and returns its box. Parent
+    // chains are checked for loops when the layout file is loaded.
+    fn layout_text_element(
+        &mut self,
+        element_key: &str,
+        context: &GameContext,
+        variables: &GameVariables,
+        layouts: &mut HashMap<String, ElementLayout>,
+    ) -> Result<Rect, GameError> {
+        let screen = Rect::new(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT);
+        if let Some(layout) = layouts.get(element_key) {
+            return Ok(layout.bounds);
+        }
+        let Some(element) = context.text_elements.get(element_key) else {
+            return Ok(screen);
+        };
+        let reference = match &element.parent {
+            Some(parent) => self.layout_text_element(parent, context, variables, layouts)?,
+            None => screen,
+        };
+
+        let theme = context.theme();
+        let scale = self.text_scale;
+        let inputs = element
+            .lines
+            .iter()
+            .map(|(line_key, line)| {
+                let text = line.resolved_text(variables);
+                // Broken markup from a template is shown as it is
+                let mut runs =
+                    rich_text::parse(&text).unwrap_or_else(|_| vec![TextRun::plain(&text)]);
+                if runs.is_empty() {
+                    runs.push(TextRun::plain(""));
+                }
+                let runs = runs
+                    .into_iter()
+                    .map(|run| {
+                        let mut font = run_font(theme, &line.font, &run.style);
+                        font.font_size = (font.font_size as f32 * scale).round() as u16;
+                        StyledRun {
+                            text: run.text,
+                            font,
+                            is_key: run.is_key,
+                        }
+                    })
+                    .collect();
+                LayoutInput {
+                    key: line_key,
+                    order: line.vertical_order,
+                    runs,
+                    alignment: &line.alignment,
+                    offset: (line.position.x, line.position.y),
+                }
+            })
+            .collect();
+
+        // Fonts are sized for the physical resolution, layout works in logical pixels
+        let ttf_context = self.ttf_context;
+        let text_cache = &mut self.text_cache;
+        let layout = layout_element(element, inputs, reference, |text, font| {
+            let (width, height) = text_cache.measure(ttf_context, font, text)?;
+            Ok((
+                (width as f32 / scale).round() as u32,
+                (height as f32 / scale).round() as u32,
+            ))
+        })?;
+
+        let bounds = layout.bounds;
+        layouts.insert(element_key.to_string(), layout);
+        Ok(bounds)
+    }
+
+    fn draw_transition(&mut self, context: &GameContext) -> Result<(), GameError> {
+        let alpha = context.transition_alpha();
+        if alpha == 0 {
+            return Ok(());
+        }
+        self.create_overlay(Color::RGBA(0, 0, 0, alpha))
+    }
+
+    fn create_overlay(&mut self, color: Color) -> Result<(), GameError> {
+        self.backend.set_draw_color(color);
+        let overlay = Rect::new(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT);
+        self.backend.fill_rect(overlay)
+    }
+
+    fn draw_outline(&mut self, rect: Rect, color: Color) -> Result<(), GameError> {
+        let (left, top) = (rect.left(), rect.top());
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+        self.backend.set_draw_color(color);
+        self.backend.draw_line((left, top), (right, top))?;
+        self.backend.draw_line((right, top), (right, bottom))?;
+        self.backend.draw_line((right, bottom), (left, bottom))?;
+        self.backend.draw_line((left, bottom), (left, top))
+    }
+}
+
+impl<'ttf, B: RenderBackend> EventSubscriber for Renderer<'ttf, B> {
+    // Start the effects for what happened in the game since the last frame
+    fn handle_events(&mut self, events: &[GameEvent], context: &GameContext) {
+        let scale = context.settings.effects.particle_scale();
+        if scale == 0.0 {
+            self.particles.clear();
+            return;
+        }
+        let theme = context.theme();
+        let count = |full: usize| ((full as f32 * scale).round() as usize).max(1);
+
+        for event in events {
+            match event {
+                GameEvent::FoodEaten { at } => {
+                    self.particles
+                        .burst(cell_center(at), count(24), 3.0, theme.food, 30);
+                }
+                GameEvent::PowerUpCollected { at } => {
+                    self.particles
+                        .burst(cell_center(at), count(16), 1.5, theme.power_up, 45);
+                    self.particles
+                        .burst(cell_center(at), count(8), 2.5, Color::WHITE, 25);
+                }
+                GameEvent::Wrapped { exit, entry } => {
+                    // Flashing is exactly what reduced motion asks to avoid
+                    if context.settings.reduced_motion {
+                        continue;
+                    }
+                    for point in [exit, entry] {
+                        self.particles.flash(
+                            cell_center(point),
+                            DOT_SIZE_IN_PXS * 2,
+                            theme.snake_head,
+                            12,
+                        );
+                    }
+                }
+                GameEvent::Died { segments, .. } => {
+                    for (i, segment) in segments.iter().enumerate() {
+                        let color = theme.snake_segment(i, segments.len());
+                        self.particles
+                            .burst(cell_center(segment), count(6), 4.0, color, 50);
+                    }
+                }
+                // Everything else has no particles
+                _ => {}
+            }
+        }
+    }
+}
+
+fn cell_center(point: &Point) -> (f32, f32) {
+    let size = DOT_SIZE_IN_PXS as f32;
+    (
+        point.0 as f32 * size + size / 2.0,
+        point.1 as f32 * size + size / 2.0,
+    )
+}
+
+// Font of a styled run, starting from the line's font as the theme shows it
+fn run_font(theme: &Theme, line_font: &FontDefinition, style: &RunStyle) -> FontDefinition {
+    let mut font = theme.resolve_font(line_font);
+    if let Some(font_name) = style.font_name {
+        font.font_name = font_name;
+    }
+    if let Some(font_size) = style.font_size {
+        font.font_size = font_size;
+    }
+    font.color = match style.color {
+        RunColor::Line => font.color,
+        RunColor::Role(role) => theme.resolve_font(&line_font.with_style(role)).color,
+        RunColor::Fixed(color) => color,
+    };
+    font
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::high_scores::HighScores;
+
+    fn render(renderer: &mut Renderer<SoftwareBackend>, context: &GameContext) -> Frame {
+        renderer.request_capture();
+        renderer.draw(context).unwrap();
+        renderer.take_capture().unwrap()
+    }
+
+    #[test]
+    fn captures_the_whole_screen_in_theme_colors() {
+        let ttf_context = sdl2::ttf::init().unwrap();
+        let mut renderer = Renderer::new_headless(&ttf_context).unwrap();
+        let context = GameContext::with_high_scores(HighScores::new(), None);
+        let frame = render(&mut renderer, &context);
+
+        assert_eq!((frame.width, frame.height), (SCREEN_WIDTH, SCREEN_HEIGHT));
+        assert_eq!(
+            frame.pixels.len(),
+            (SCREEN_WIDTH * SCREEN_HEIGHT * 4) as usize
+        );
+        let background = context.theme().background(context.state);
+        assert_eq!(frame.pixel(0, 0), background);
+        assert_eq!(frame.pixel(SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1), background);
+    }
+
+    #[test]
+    fn frames_are_only_captured_on_request_and_repeat_exactly() {
+        let ttf_context = sdl2::ttf::init().unwrap();
+        let mut renderer = Renderer::new_headless(&ttf_context).unwrap();
+        let context = GameContext::with_high_scores(HighScores::new(), None);
+        renderer.draw(&context).unwrap();
+        assert!(renderer.take_capture().is_none());
+
+        let first = render(&mut renderer, &context);
+        let second = render(&mut renderer, &context);
+        assert!(first.pixels == second.pixels);
+        assert!(renderer.take_capture().is_none());
+    }
+}
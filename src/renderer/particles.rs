@@ -0,0 +1,106 @@
+extern crate sdl2;
+
+use crate::color::Color;
+use rand::Rng;
+use sdl2::rect::Rect;
+
+use crate::error::GameError;
+use crate::renderer::backend::RenderBackend;
+
+pub struct Particle {
+    x: f32,
+    y: f32,
+    velocity_x: f32,
+    velocity_y: f32,
+    size: u32,
+    color: Color,
+    life: u32,
+    max_life: u32,
+}
+
+// Short lived squares that fade out, all positions are in screen pixels
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> ParticleSystem {
+        ParticleSystem {
+            particles: Vec::new(),
+        }
+    }
+
+    // `count` particles flying out of `center` in random directions
+    pub fn burst(&mut self, center: (f32, f32), count: usize, speed: f32, color: Color, life: u32) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = speed * rng.gen_range(0.3..1.0);
+            self.particles.push(Particle {
+                x: center.0,
+                y: center.1,
+                velocity_x: angle.cos() * speed,
+                velocity_y: angle.sin() * speed,
+                size: rng.gen_range(2..5),
+                color,
+                life: rng.gen_range(life / 2..=life),
+                max_life: life,
+            });
+        }
+    }
+
+    // A square that stays in place and fades, used for flashes
+    pub fn flash(&mut self, center: (f32, f32), size: u32, color: Color, life: u32) {
+        self.particles.push(Particle {
+            x: center.0,
+            y: center.1,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            size,
+            color,
+            life,
+            max_life: life,
+        });
+    }
+
+    pub fn update(&mut self) {
+        for particle in &mut self.particles {
+            particle.x += particle.velocity_x;
+            particle.y += particle.velocity_y;
+            particle.velocity_x *= 0.94;
+            particle.velocity_y *= 0.94;
+            particle.life -= 1;
+        }
+        self.particles.retain(|particle| particle.life > 0);
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn draw<B: RenderBackend>(
+        &self,
+        backend: &mut B,
+        offset: (i32, i32),
+    ) -> Result<(), GameError> {
+        for particle in &self.particles {
+            let alpha = particle.life * 255 / particle.max_life;
+            let Color { r, g, b, .. } = particle.color;
+            backend.set_draw_color(Color::RGBA(r, g, b, alpha as u8));
+            let half = (particle.size / 2) as i32;
+            backend.fill_rect(Rect::new(
+                particle.x as i32 - half + offset.0,
+                particle.y as i32 - half + offset.1,
+                particle.size,
+                particle.size,
+            ))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+extern crate sdl2;
+
+use crate::color::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Texture;
+use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
+
+use crate::assets::{self, AssetSource};
+use crate::error::GameError;
+use crate::game_context::{PlayerDirection, Point};
+use crate::renderer::backend::RenderBackend;
+
+// Tiles in the atlas, left to right. Base orientations: head faces right, straight runs
+// left-right, corner joins right and down, tail joins its neighbour on the right.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SpriteKind {
+    Head,
+    Straight,
+    Corner,
+    Tail,
+    Food,
+}
+
+pub struct SpriteAtlas {
+    pub texture: Texture,
+    tile_size: u32,
+}
+
+impl SpriteAtlas {
+    // Magenta pixels in the bitmap are transparent
+    pub fn load<B: RenderBackend>(
+        backend: &B,
+        path: &str,
+        tile_size: u32,
+    ) -> Result<SpriteAtlas, GameError> {
+        let error = |message: String| GameError::Asset {
+            path: assets::path(path),
+            message,
+        };
+        let mut surface = match assets::locate(path)? {
+            AssetSource::File(file) => Surface::load_bmp(file),
+            AssetSource::Embedded(bytes) => {
+                RWops::from_bytes(bytes).and_then(|mut rwops| Surface::load_bmp_rw(&mut rwops))
+            }
+        }
+        .map_err(error)?;
+        surface
+            .set_color_key(true, Color::MAGENTA.into())
+            .map_err(GameError::Render)?;
+        let texture = backend.create_texture_from_surface(&surface)?;
+        Ok(SpriteAtlas { texture, tile_size })
+    }
+
+    pub fn source(&self, kind: SpriteKind) -> Rect {
+        let index = match kind {
+            SpriteKind::Head => 0,
+            SpriteKind::Straight => 1,
+            SpriteKind::Corner => 2,
+            SpriteKind::Tail => 3,
+            SpriteKind::Food => 4,
+        };
+        Rect::new(
+            (index * self.tile_size) as i32,
+            0,
+            self.tile_size,
+            self.tile_size,
+        )
+    }
+}
+
+// Pick the sprite and clockwise rotation in degrees for every segment of the snake
+pub fn snake_sprites(
+    segments: &[Point],
+    head_direction: PlayerDirection,
+) -> Vec<(SpriteKind, f64)> {
+    let last = segments.len().saturating_sub(1);
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            if i == 0 {
+                return (SpriteKind::Head, angle(head_direction));
+            }
+            let toward_head = direction_between(*segment, segments[i - 1]);
+            if i == last {
+                return (SpriteKind::Tail, angle(toward_head));
+            }
+            let toward_tail = direction_between(*segment, segments[i + 1]);
+            body_sprite(toward_head, toward_tail)
+        })
+        .collect()
+}
+
+fn body_sprite(a: PlayerDirection, b: PlayerDirection) -> (SpriteKind, f64) {
+    use PlayerDirection::*;
+    match (a, b) {
+        (Left, Right) | (Right, Left) => (SpriteKind::Straight, 0.0),
+        (Up, Down) | (Down, Up) => (SpriteKind::Straight, 90.0),
+        (Right, Down) | (Down, Right) => (SpriteKind::Corner, 0.0),
+        (Down, Left) | (Left, Down) => (SpriteKind::Corner, 90.0),
+        (Left, Up) | (Up, Left) => (SpriteKind::Corner, 180.0),
+        (Up, Right) | (Right, Up) => (SpriteKind::Corner, 270.0),
+        // Only while growing, when the new segment still sits on top of the old tail
+        _ => (SpriteKind::Straight, angle(a)),
+    }
+}
+
+// Neighbours more than a cell apart are joined across the board edge in Wall Pass
+fn direction_between(from: Point, to: Point) -> PlayerDirection {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    if dx.abs() >= dy.abs() {
+        if (dx > 0) == (dx.abs() == 1) {
+            PlayerDirection::Right
+        } else {
+            PlayerDirection::Left
+        }
+    } else if (dy > 0) == (dy.abs() == 1) {
+        PlayerDirection::Down
+    } else {
+        PlayerDirection::Up
+    }
+}
+
+fn angle(direction: PlayerDirection) -> f64 {
+    match direction {
+        PlayerDirection::Right => 0.0,
+        PlayerDirection::Down => 90.0,
+        PlayerDirection::Left => 180.0,
+        PlayerDirection::Up => 270.0,
+    }
+}
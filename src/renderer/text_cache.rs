@@ -0,0 +1,315 @@
+extern crate sdl2;
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::assets::{self, AssetSource};
+use crate::color::Color;
+use crate::constants::{FALLBACK_FONTS, FONT_PATH};
+use crate::entities::text_elements::{FontDefinition, FontName, TextElement};
+use crate::error::GameError;
+use crate::renderer::backend::RenderBackend;
+use sdl2::render::{Texture, TextureQuery};
+use sdl2::rwops::RWops;
+use sdl2::ttf::{Font, Sdl2TtfContext};
+
+#[derive(Default)]
+pub struct RenderMetrics {
+    pub frames: u64,
+    pub font_loads: u64,
+    pub font_cache_hits: u64,
+    pub text_renders: u64,
+    pub text_cache_hits: u64,
+    pub last_frame_time: Duration,
+    pub total_frame_time: Duration,
+}
+
+impl Display for RenderMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let average = if self.frames > 0 {
+            self.total_frame_time / self.frames as u32
+        } else {
+            Duration::ZERO
+        };
+        write!(
+            f,
+            "{} frames, {:?} avg frame, fonts: {} loaded / {} cached, text: {} rendered / {} cached",
+            self.frames,
+            average,
+            self.font_loads,
+            self.font_cache_hits,
+            self.text_renders,
+            self.text_cache_hits
+        )
+    }
+}
+
+pub struct CachedText {
+    pub texture: Texture,
+    pub width: u32,
+    pub height: u32,
+    text: String,
+    font_name: FontName,
+    font_size: u16,
+    color: Color,
+}
+
+impl CachedText {
+    fn matches(&self, text: &str, font: &FontDefinition) -> bool {
+        self.text == text
+            && self.font_name == font.font_name
+            && self.font_size == font.font_size
+            && self.color == font.color
+    }
+}
+
+type SizeKey = (String, FontName, u16);
+
+fn size_key(text: &str, font: &FontDefinition) -> SizeKey {
+    (text.to_string(), font.font_name, font.font_size)
+}
+
+// Fonts are loaded once per (file, size) and each line keeps its texture until it changes
+pub struct TextCache<'ttf> {
+    fonts: HashMap<(String, u16), Font<'ttf, 'static>>,
+    // Font sizes asked for since the last prune, fonts of other sizes are dropped then
+    used_sizes: HashSet<u16>,
+    texts: HashMap<(String, String), CachedText>,
+    // Sizes of the cached and measured texts, so layout doesn't search every texture
+    sizes: HashMap<SizeKey, (u32, u32)>,
+    // Font files tried for text the requested font has no glyphs for
+    fallback_fonts: Vec<String>,
+    // Fallback fonts that failed to load, so they're only reported once
+    missing_fonts: HashSet<String>,
+    pub metrics: RenderMetrics,
+}
+
+impl<'ttf> Default for TextCache<'ttf> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'ttf> TextCache<'ttf> {
+    pub fn new() -> TextCache<'ttf> {
+        TextCache {
+            fonts: HashMap::new(),
+            used_sizes: HashSet::new(),
+            texts: HashMap::new(),
+            sizes: HashMap::new(),
+            fallback_fonts: FALLBACK_FONTS.iter().map(|font| font.to_string()).collect(),
+            missing_fonts: HashSet::new(),
+            metrics: RenderMetrics::default(),
+        }
+    }
+
+    pub fn get_text<B: RenderBackend>(
+        &mut self,
+        ttf_context: &'ttf Sdl2TtfContext,
+        backend: &B,
+        element_key: &str,
+        line_key: &str,
+        text: &str,
+        font: &FontDefinition,
+    ) -> Result<&mut CachedText, GameError> {
+        let key = (element_key.to_string(), line_key.to_string());
+        if self
+            .texts
+            .get(&key)
+            .is_some_and(|cached| cached.matches(text, font))
+        {
+            self.metrics.text_cache_hits += 1;
+            return Ok(self.texts.get_mut(&key).unwrap());
+        }
+
+        let loaded_font = self.font_for_text(ttf_context, font, text)?;
+
+        // render a surface, and convert it to a texture bound to the canvas
+        let surface = loaded_font
+            .render(text)
+            .blended(font.color)
+            .map_err(|e| GameError::Render(e.to_string()))?;
+        let texture = backend.create_texture_from_surface(&surface)?;
+        let TextureQuery { width, height, .. } = texture.query();
+        self.metrics.text_renders += 1;
+        self.sizes.insert(size_key(text, font), (width, height));
+
+        let cached = CachedText {
+            texture,
+            width,
+            height,
+            text: text.to_string(),
+            font_name: font.font_name,
+            font_size: font.font_size,
+            color: font.color,
+        };
+        if let Some(old) = self.texts.insert(key.clone(), cached) {
+            // SAFETY: the canvas that created the texture is still alive
+            unsafe { old.texture.destroy() };
+        }
+        Ok(self.texts.get_mut(&key).unwrap())
+    }
+
+    // Size the text would have when rendered, taken from its texture when it already has one
+    pub fn measure(
+        &mut self,
+        ttf_context: &'ttf Sdl2TtfContext,
+        font: &FontDefinition,
+        text: &str,
+    ) -> Result<(u32, u32), GameError> {
+        let key = size_key(text, font);
+        if let Some(size) = self.sizes.get(&key) {
+            return Ok(*size);
+        }
+        let loaded_font = self.font_for_text(ttf_context, font, text)?;
+        let size = loaded_font
+            .size_of(text)
+            .map_err(|e| GameError::Render(e.to_string()))?;
+        self.sizes.insert(key, size);
+        Ok(size)
+    }
+
+    // Drop textures for lines that no longer exist, e.g. menu items that were removed.
+    // Pieces of wrapped lines are cached as `line_key#n`.
+    pub fn prune(&mut self, text_elements: &HashMap<String, TextElement>) {
+        let stale: Vec<(String, String)> = self
+            .texts
+            .keys()
+            .filter(|(element_key, cache_key)| {
+                let line_key = cache_key.split('#').next().unwrap_or(cache_key);
+                !text_elements
+                    .get(element_key)
+                    .is_some_and(|element| element.lines.contains_key(line_key))
+            })
+            .cloned()
+            .collect();
+        for key in stale {
+            if let Some(old) = self.texts.remove(&key) {
+                // SAFETY: the canvas that created the texture is still alive
+                unsafe { old.texture.destroy() };
+            }
+        }
+
+        let kept: HashSet<SizeKey> = self
+            .texts
+            .values()
+            .map(|cached| (cached.text.clone(), cached.font_name, cached.font_size))
+            .collect();
+        self.sizes.retain(|key, _| kept.contains(key));
+
+        // Fonts of sizes nothing is drawn at anymore, e.g. after the window was resized
+        let mut sizes = std::mem::take(&mut self.used_sizes);
+        sizes.extend(self.texts.values().map(|cached| cached.font_size));
+        self.fonts.retain(|(_, size), _| sizes.contains(size));
+    }
+
+    // Fonts of the selected language go before the built-in fallbacks. Rendered text is
+    // dropped when they change since it may have been drawn with another font.
+    pub fn set_language_fonts(&mut self, language_fonts: &[String]) {
+        let fallback_fonts: Vec<String> = language_fonts
+            .iter()
+            .cloned()
+            .chain(FALLBACK_FONTS.iter().map(|font| font.to_string()))
+            .collect();
+        if fallback_fonts == self.fallback_fonts {
+            return;
+        }
+        self.fallback_fonts = fallback_fonts;
+        self.sizes.clear();
+        for (_, old) in self.texts.drain() {
+            // SAFETY: the canvas that created the texture is still alive
+            unsafe { old.texture.destroy() };
+        }
+    }
+
+    // The requested font when it has every glyph of the text, otherwise the first fallback
+    // font that does, e.g. for scripts the arcade fonts don't cover
+    fn font_for_text(
+        &mut self,
+        ttf_context: &'ttf Sdl2TtfContext,
+        font: &FontDefinition,
+        text: &str,
+    ) -> Result<&Font<'ttf, 'static>, GameError> {
+        self.used_sizes.insert(font.font_size);
+        let font_loads = self.metrics.font_loads;
+        let own_file = font_file(font.font_name);
+        let mut chosen = own_file.to_string();
+        let candidates = std::iter::once(own_file.to_string()).chain(self.fallback_fonts.clone());
+        for file in candidates {
+            if self.missing_fonts.contains(&file) {
+                continue;
+            }
+            let loaded = match TextCache::font(
+                &mut self.fonts,
+                &mut self.metrics,
+                ttf_context,
+                &file,
+                font.font_size,
+            ) {
+                Ok(loaded) => loaded,
+                // Only the arcade fonts have to be there
+                Err(e) if file != own_file => {
+                    eprintln!("Could not load fallback font {file}: {e}");
+                    self.missing_fonts.insert(file);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let has_glyphs = text
+                .chars()
+                .all(|c| c.is_whitespace() || loaded.find_glyph(c).is_some());
+            if has_glyphs {
+                chosen = file;
+                break;
+            }
+        }
+        // Only counted as a hit when none of the fonts tried had to be loaded
+        if self.metrics.font_loads == font_loads {
+            self.metrics.font_cache_hits += 1;
+        }
+        TextCache::font(
+            &mut self.fonts,
+            &mut self.metrics,
+            ttf_context,
+            &chosen,
+            font.font_size,
+        )
+    }
+
+    fn font<'a>(
+        fonts: &'a mut HashMap<(String, u16), Font<'ttf, 'static>>,
+        metrics: &mut RenderMetrics,
+        ttf_context: &'ttf Sdl2TtfContext,
+        file: &str,
+        font_size: u16,
+    ) -> Result<&'a Font<'ttf, 'static>, GameError> {
+        match fonts.entry((file.to_string(), font_size)) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let relative = format!("{FONT_PATH}/{file}");
+                let error = |message: String| GameError::Font {
+                    path: assets::path(&relative),
+                    message,
+                };
+                let font = match assets::locate(&relative)? {
+                    AssetSource::File(path) => ttf_context.load_font(path, font_size),
+                    AssetSource::Embedded(bytes) => RWops::from_bytes(bytes)
+                        .and_then(|rwops| ttf_context.load_font_from_rwops(rwops, font_size)),
+                }
+                .map_err(error)?;
+                metrics.font_loads += 1;
+                Ok(entry.insert(font))
+            }
+        }
+    }
+}
+
+fn font_file(font_name: FontName) -> &'static str {
+    match font_name {
+        FontName::ArcadeInterlaced => "ArcadeInterlaced-O4d.ttf",
+        FontName::ArcadeNormal => "ArcadeNormal-ZDZ.ttf",
+        FontName::ArcadeRounded => "ArcadeRounded-3DM.ttf",
+    }
+}
@@ -0,0 +1,191 @@
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum GameSpeed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl GameSpeed {
+    // Number of frames between two game ticks
+    pub fn tick_frames(&self) -> u32 {
+        match *self {
+            GameSpeed::Slow => 14,
+            GameSpeed::Normal => 10,
+            GameSpeed::Fast => 6,
+        }
+    }
+
+    pub fn next(&self) -> GameSpeed {
+        match *self {
+            GameSpeed::Slow => GameSpeed::Normal,
+            GameSpeed::Normal => GameSpeed::Fast,
+            GameSpeed::Fast => GameSpeed::Slow,
+        }
+    }
+
+    // Key of the name shown in menus, see assets/i18n
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            GameSpeed::Slow => "speed_slow",
+            GameSpeed::Normal => "speed_normal",
+            GameSpeed::Fast => "speed_fast",
+        }
+    }
+}
+
+// Palettes layered over the selected theme for players who can't tell its colors apart
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ColorPreset {
+    Theme,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl ColorPreset {
+    pub fn next(&self) -> ColorPreset {
+        match *self {
+            ColorPreset::Theme => ColorPreset::Deuteranopia,
+            ColorPreset::Deuteranopia => ColorPreset::Protanopia,
+            ColorPreset::Protanopia => ColorPreset::Tritanopia,
+            ColorPreset::Tritanopia => ColorPreset::HighContrast,
+            ColorPreset::HighContrast => ColorPreset::Theme,
+        }
+    }
+
+    // Key of the name shown in menus, see assets/i18n
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            ColorPreset::Theme => "colors_theme",
+            ColorPreset::Deuteranopia => "colors_deuteranopia",
+            ColorPreset::Protanopia => "colors_protanopia",
+            ColorPreset::Tritanopia => "colors_tritanopia",
+            ColorPreset::HighContrast => "colors_high_contrast",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum EffectsQuality {
+    Off,
+    Low,
+    High,
+}
+
+impl EffectsQuality {
+    // Share of the full particle count that gets spawned
+    pub fn particle_scale(&self) -> f32 {
+        match *self {
+            EffectsQuality::Off => 0.0,
+            EffectsQuality::Low => 0.35,
+            EffectsQuality::High => 1.0,
+        }
+    }
+
+    pub fn next(&self) -> EffectsQuality {
+        match *self {
+            EffectsQuality::Off => EffectsQuality::Low,
+            EffectsQuality::Low => EffectsQuality::High,
+            EffectsQuality::High => EffectsQuality::Off,
+        }
+    }
+
+    // Key of the name shown in menus, see assets/i18n
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            EffectsQuality::Off => "effects_off",
+            EffectsQuality::Low => "effects_low",
+            EffectsQuality::High => "effects_high",
+        }
+    }
+}
+
+// Timings in frames for the flash, scatter/fade and screen shake played when the snake dies
+#[derive(Copy, Clone)]
+pub struct DeathSequence {
+    pub flash_frames: u32,
+    pub fade_frames: u32,
+    pub shake_frames: u32,
+    pub shake_intensity: u32,
+    pub scatter_speed: f32,
+    pub flashing: bool,
+}
+
+impl Default for DeathSequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeathSequence {
+    pub fn new() -> DeathSequence {
+        DeathSequence {
+            flash_frames: 24,
+            fade_frames: 45,
+            shake_frames: 20,
+            shake_intensity: 8,
+            scatter_speed: 0.4,
+            flashing: true,
+        }
+    }
+
+    // Same timings without the flashing, shaking and flying segments
+    pub fn without_motion(&self) -> DeathSequence {
+        DeathSequence {
+            shake_intensity: 0,
+            scatter_speed: 0.0,
+            flashing: false,
+            ..*self
+        }
+    }
+}
+
+// Volumes go from silent at 0 up to full at this step
+pub const VOLUME_STEPS: u8 = 10;
+
+pub struct Settings {
+    pub speed: GameSpeed,
+    pub death_sequence: DeathSequence,
+    // Index into the themes found in assets/themes
+    pub theme: usize,
+    pub color_preset: ColorPreset,
+    // Draw food, power-ups and the snake's head with distinct shapes, not just colors
+    pub shape_markers: bool,
+    // No flashing, shaking or screen fades
+    pub reduced_motion: bool,
+    // Slide the snake between cells instead of jumping a cell every tick
+    pub smooth_movement: bool,
+    pub effects: EffectsQuality,
+    // Index into the catalogs found in assets/i18n, English comes first
+    pub language: usize,
+    pub music_volume: u8,
+    pub sound_volume: u8,
+    // Silences music and sound without losing the volumes
+    pub muted: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            speed: GameSpeed::Normal,
+            death_sequence: DeathSequence::new(),
+            theme: 0,
+            color_preset: ColorPreset::Theme,
+            shape_markers: false,
+            reduced_motion: false,
+            smooth_movement: false,
+            effects: EffectsQuality::High,
+            language: 0,
+            music_volume: 6,
+            sound_volume: 8,
+            muted: false,
+        }
+    }
+}
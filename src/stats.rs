@@ -0,0 +1,73 @@
+use crate::{
+    constants::FRAMES_PER_SECOND,
+    entities::actors::ActorKind,
+    events::{EventSubscriber, GameEvent},
+    game_context::{GameContext, GameState},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DeathCause {
+    Wall,
+    SelfCollision,
+    Hazard(ActorKind),
+    OneWayGate,
+    BreakableWall,
+}
+
+impl DeathCause {
+    // Key of the message shown on the game over screen, see assets/i18n
+    pub fn message_key(&self) -> &'static str {
+        match *self {
+            DeathCause::Wall => "death_wall",
+            DeathCause::SelfCollision => "death_self_collision",
+            DeathCause::Hazard(ActorKind::BouncingBall) => "death_bouncing_ball",
+            DeathCause::Hazard(ActorKind::PatrolBlock) => "death_patrol_block",
+            DeathCause::Hazard(ActorKind::Mouse) => "death_mouse",
+            DeathCause::OneWayGate => "death_one_way_gate",
+            DeathCause::BreakableWall => "death_breakable_wall",
+        }
+    }
+}
+
+pub struct RunStats {
+    pub frames_alive: u32,
+    pub food_eaten: u32,
+    pub cause_of_death: Option<DeathCause>,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunStats {
+    pub fn new() -> RunStats {
+        RunStats {
+            frames_alive: 0,
+            food_eaten: 0,
+            cause_of_death: None,
+        }
+    }
+
+    // Time alive formatted as minutes:seconds
+    pub fn time_alive(&self) -> String {
+        let seconds = self.frames_alive / FRAMES_PER_SECOND;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl EventSubscriber for RunStats {
+    fn handle_events(&mut self, events: &[GameEvent], context: &GameContext) {
+        if context.state == GameState::Playing && !context.is_counting_down() {
+            self.frames_alive += 1;
+        }
+        for event in events {
+            match event {
+                GameEvent::FoodEaten { .. } => self.food_eaten += 1,
+                GameEvent::Died { cause, .. } => self.cause_of_death = Some(*cause),
+                _ => {}
+            }
+        }
+    }
+}
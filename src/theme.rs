@@ -0,0 +1,320 @@
+use crate::{
+    assets,
+    color::Color,
+    data::kv_file::KvFile,
+    entities::{
+        actors::ActorKind,
+        terrain::TerrainTile,
+        text_elements::{FontDefinition, FontName, TextStyle},
+    },
+    error::GameError,
+    game_context::GameState,
+    settings::ColorPreset,
+};
+
+#[derive(Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background_playing: Color,
+    pub background_paused: Color,
+    pub background_over: Color,
+    pub background_menu: Color,
+    pub death_flash: Color,
+    // The snake's color on the flashes of its death
+    pub snake_death_flash: Color,
+    pub snake_head: Color,
+    pub snake_tail: Color,
+    pub snake_wall_breaker: Color,
+    pub food: Color,
+    pub power_up: Color,
+    pub ice: Color,
+    pub conveyor: Color,
+    pub mud: Color,
+    pub gate: Color,
+    pub wall: Color,
+    pub wall_detail: Color,
+    pub terrain_marker: Color,
+    pub bouncing_ball: Color,
+    pub patrol_block: Color,
+    pub mouse: Color,
+    pub overlay: Color,
+    pub text: Color,
+    pub text_highlight: Color,
+    pub font_title: FontName,
+    pub font_body: FontName,
+    pub font_hud: FontName,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Theme {
+    // The original look, also the base every theme file overrides
+    pub fn new() -> Theme {
+        Theme {
+            name: String::from("Classic"),
+            background_playing: Color::RGB(0, 0, 0),
+            background_paused: Color::RGB(30, 30, 30),
+            background_over: Color::RGB(60, 60, 60),
+            background_menu: Color::RGB(15, 15, 35),
+            death_flash: Color::RGB(120, 0, 0),
+            snake_death_flash: Color::WHITE,
+            snake_head: Color::GREEN,
+            snake_tail: Color::GREEN,
+            snake_wall_breaker: Color::RGB(0, 255, 200),
+            food: Color::RED,
+            power_up: Color::CYAN,
+            ice: Color::RGB(170, 220, 255),
+            conveyor: Color::RGB(70, 70, 90),
+            mud: Color::RGB(100, 70, 40),
+            gate: Color::RGB(40, 110, 110),
+            wall: Color::RGB(150, 60, 40),
+            wall_detail: Color::RGB(90, 30, 20),
+            terrain_marker: Color::RGB(220, 220, 220),
+            bouncing_ball: Color::RGB(255, 165, 0),
+            patrol_block: Color::RGB(160, 32, 240),
+            mouse: Color::RGB(190, 190, 190),
+            overlay: Color::RGBA(0, 0, 0, 200),
+            text: Color::WHITE,
+            text_highlight: Color::YELLOW,
+            font_title: FontName::ArcadeInterlaced,
+            font_body: FontName::ArcadeRounded,
+            font_hud: FontName::ArcadeNormal,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Theme, GameError> {
+        let file = KvFile::load(path)?;
+        let mut theme = Theme::new();
+
+        for entry in &file.entries {
+            match entry.section.as_str() {
+                "" if entry.key == "name" => theme.name = entry.value.clone(),
+                "colors" => {
+                    let color = parse_color(&entry.value)
+                        .ok_or(file.error(entry, "expected a color like #RRGGBB or #RRGGBBAA"))?;
+                    let slot = theme
+                        .color_slot(&entry.key)
+                        .ok_or(file.error(entry, &format!("unknown color `{}`", entry.key)))?;
+                    *slot = color;
+                }
+                "fonts" => {
+                    let font = FontName::from_key(&entry.value)
+                        .ok_or(file.error(entry, &format!("unknown font `{}`", entry.value)))?;
+                    match entry.key.as_str() {
+                        "title" => theme.font_title = font,
+                        "body" => theme.font_body = font,
+                        "hud" => theme.font_hud = font,
+                        _ => {
+                            return Err(
+                                file.error(entry, &format!("unknown font role `{}`", entry.key))
+                            )
+                        }
+                    }
+                }
+                _ => {
+                    return Err(file.error(
+                        entry,
+                        &format!("unexpected `{}` in section [{}]", entry.key, entry.section),
+                    ))
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    // Every *.theme file in the directory, sorted by file name. Broken files are reported
+    // and skipped, and the built-in theme is used when none load.
+    pub fn load_all(dir: &str) -> Vec<Theme> {
+        let paths = assets::list(dir, "theme");
+        let mut themes: Vec<Theme> = paths
+            .iter()
+            .filter_map(|path| match Theme::load(path) {
+                Ok(theme) => Some(theme),
+                Err(e) => {
+                    eprintln!("{e}");
+                    None
+                }
+            })
+            .collect();
+        if themes.is_empty() {
+            themes.push(Theme::new());
+        }
+        themes
+    }
+
+    // Recolor what matters during play, the rest of the theme stays as it is
+    pub fn with_preset(self, preset: ColorPreset) -> Theme {
+        match preset {
+            ColorPreset::Theme => self,
+            // Blue and orange stay apart for red-green color blindness
+            ColorPreset::Deuteranopia | ColorPreset::Protanopia => Theme {
+                snake_head: Color::RGB(86, 180, 233),
+                snake_tail: Color::RGB(0, 114, 178),
+                snake_wall_breaker: Color::WHITE,
+                food: Color::RGB(230, 159, 0),
+                power_up: Color::RGB(240, 228, 66),
+                // Protanopes see red as darker, so their hazards are brighter
+                bouncing_ball: if preset == ColorPreset::Protanopia {
+                    Color::RGB(255, 140, 40)
+                } else {
+                    Color::RGB(213, 94, 0)
+                },
+                patrol_block: Color::RGB(204, 121, 167),
+                mouse: Color::RGB(190, 190, 190),
+                death_flash: Color::RGB(90, 90, 90),
+                text_highlight: Color::RGB(230, 159, 0),
+                ..self
+            },
+            // Red and cyan stay apart for blue-yellow color blindness
+            ColorPreset::Tritanopia => Theme {
+                snake_head: Color::RGB(0, 200, 200),
+                snake_tail: Color::RGB(0, 140, 140),
+                snake_wall_breaker: Color::WHITE,
+                food: Color::RGB(255, 60, 60),
+                power_up: Color::RGB(255, 150, 200),
+                bouncing_ball: Color::RGB(200, 0, 90),
+                patrol_block: Color::RGB(140, 140, 140),
+                mouse: Color::RGB(220, 220, 220),
+                death_flash: Color::RGB(90, 90, 90),
+                text_highlight: Color::RGB(255, 60, 60),
+                ..self
+            },
+            ColorPreset::HighContrast => Theme {
+                background_playing: Color::BLACK,
+                background_paused: Color::BLACK,
+                background_over: Color::BLACK,
+                background_menu: Color::BLACK,
+                death_flash: Color::RGB(80, 80, 80),
+                snake_death_flash: Color::YELLOW,
+                snake_head: Color::WHITE,
+                snake_tail: Color::WHITE,
+                snake_wall_breaker: Color::CYAN,
+                food: Color::YELLOW,
+                power_up: Color::CYAN,
+                ice: Color::RGB(60, 60, 60),
+                conveyor: Color::RGB(60, 60, 60),
+                mud: Color::RGB(60, 60, 60),
+                gate: Color::RGB(60, 60, 60),
+                wall: Color::RGB(160, 160, 160),
+                wall_detail: Color::BLACK,
+                terrain_marker: Color::WHITE,
+                bouncing_ball: Color::MAGENTA,
+                patrol_block: Color::MAGENTA,
+                mouse: Color::YELLOW,
+                overlay: Color::RGBA(0, 0, 0, 230),
+                text: Color::WHITE,
+                text_highlight: Color::YELLOW,
+                ..self
+            },
+        }
+    }
+
+    pub fn background(&self, state: GameState) -> Color {
+        match state {
+            GameState::Playing | GameState::Dying => self.background_playing,
+            GameState::Paused => self.background_paused,
+            GameState::Over => self.background_over,
+            GameState::Title
+            | GameState::MainMenu
+            | GameState::ModeSelect
+            | GameState::Options
+            | GameState::HighScores => self.background_menu,
+        }
+    }
+
+    pub fn terrain(&self, tile: TerrainTile) -> Option<Color> {
+        match tile {
+            TerrainTile::Floor => None,
+            TerrainTile::Ice => Some(self.ice),
+            TerrainTile::Conveyor(_) => Some(self.conveyor),
+            TerrainTile::Mud => Some(self.mud),
+            TerrainTile::OneWayGate(_) => Some(self.gate),
+            TerrainTile::BreakableWall => Some(self.wall),
+        }
+    }
+
+    pub fn actor(&self, kind: ActorKind) -> Color {
+        match kind {
+            ActorKind::BouncingBall => self.bouncing_ball,
+            ActorKind::PatrolBlock => self.patrol_block,
+            ActorKind::Mouse => self.mouse,
+        }
+    }
+
+    // Color of segment `index` out of `length`, blending from head to tail
+    pub fn snake_segment(&self, index: usize, length: usize) -> Color {
+        let t = if length > 1 {
+            index as f32 / (length - 1) as f32
+        } else {
+            0.0
+        };
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+        Color::RGB(
+            mix(self.snake_head.r, self.snake_tail.r),
+            mix(self.snake_head.g, self.snake_tail.g),
+            mix(self.snake_head.b, self.snake_tail.b),
+        )
+    }
+
+    // Font and color a line is drawn with, lines with a Fixed style keep their own
+    pub fn resolve_font(&self, font: &FontDefinition) -> FontDefinition {
+        let (font_name, color) = match font.style {
+            TextStyle::Fixed => (font.font_name, font.color),
+            TextStyle::Title => (self.font_title, self.text),
+            TextStyle::Body => (self.font_body, self.text),
+            TextStyle::Hud => (self.font_hud, self.text),
+            TextStyle::Highlight => (self.font_body, self.text_highlight),
+        };
+        FontDefinition {
+            font_name,
+            color,
+            ..*font
+        }
+    }
+
+    fn color_slot(&mut self, key: &str) -> Option<&mut Color> {
+        let slot = match key {
+            "background_playing" => &mut self.background_playing,
+            "background_paused" => &mut self.background_paused,
+            "background_over" => &mut self.background_over,
+            "background_menu" => &mut self.background_menu,
+            "death_flash" => &mut self.death_flash,
+            "snake_death_flash" => &mut self.snake_death_flash,
+            "snake_head" => &mut self.snake_head,
+            "snake_tail" => &mut self.snake_tail,
+            "snake_wall_breaker" => &mut self.snake_wall_breaker,
+            "food" => &mut self.food,
+            "power_up" => &mut self.power_up,
+            "ice" => &mut self.ice,
+            "conveyor" => &mut self.conveyor,
+            "mud" => &mut self.mud,
+            "gate" => &mut self.gate,
+            "wall" => &mut self.wall,
+            "wall_detail" => &mut self.wall_detail,
+            "terrain_marker" => &mut self.terrain_marker,
+            "bouncing_ball" => &mut self.bouncing_ball,
+            "patrol_block" => &mut self.patrol_block,
+            "mouse" => &mut self.mouse,
+            "overlay" => &mut self.overlay,
+            "text" => &mut self.text,
+            "text_highlight" => &mut self.text_highlight,
+            _ => return None,
+        };
+        Some(slot)
+    }
+}
+
+// #RRGGBB or #RRGGBBAA
+pub fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::RGBA(channel(0)?, channel(2)?, channel(4)?, alpha))
+}